@@ -1,22 +1,27 @@
-use gworld::{math, World, Config, Environs, Creature};
+use gworld::{math, World, Config, Verbosity, Environs, Creature, StopCriteria};
 
 fn main() {
-	Config::set( Config {
-		inputs: ["X", "Y"].iter().map(|&s| s.into()).collect(),
-		outputs: ["MOVX", "MOVY"].iter().map(|&s| s.into()).collect(),
-		neurons: 3,
-		strength_mult: 4.0, // multiplier for gene strengths
-		population: 50, 
-		lifespan: 100, 
-		genome_size: 6, // number of chromosomes
-		use_chromo: true, // multiple genes per functional chromosome?
-		independent: false, // do the creatures (not) interact with each other?
-		verbose: "none".to_string(), // options: silent/low/high
-	});
+	tracing_subscriber::fmt::init(); // optional: gworld only talks through tracing, this prints its events
 
-	let mut world :World<MyEnv, Blob> = World::new(); 
+	let config = Config::builder() // anything left out gets a default
+		.inputs(["X", "Y"])
+		.outputs(["MOVX", "MOVY"])
+		.neurons(3)
+		.strength_mult(4.0) // multiplier for gene strengths
+		.population(50)
+		.lifespan(100)
+		.genome_size(6) // number of chromosomes
+		.use_chromo(true) // multiple genes per functional chromosome?
+		.independent(false) // do the creatures (not) interact with each other?
+		.verbose(Verbosity::Low) // options: Silent/Low/High (sent to tracing)
+		.build()
+		.expect("invalid config");
+
+	let mut world :World<MyEnv, Blob> = World::new( config ); 
 	world.live(); // will advance the world #lifespan steps 
 	world.advance( 1000 ); // will advance the world 1000 steps
+	let summary = world.run_until( StopCriteria::new().stagnation(500).max_steps(10_000) ); // or until something happens
+	println!( "Stopped after {} steps ({:?}), best fitness {:.2}", summary.steps, summary.reason, summary.best_fitness );
 	
 	// world.environs to access MyEnv structure
 	// world.organisms[i].creature to access Blob creatures
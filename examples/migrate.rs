@@ -1,32 +1,33 @@
 use std::io::stdin;
 use rand::Rng;
-use gworld::{math, World, Config, Environs, Creature};
+use gworld::{math, World, Config, Verbosity, Environs, Creature};
 
 // Goal: Blob object will evolve to travel "up" (toward y=0) on the map. 
 // Press Enter to continue the execution. Ctrl-C to stop. (duh)
 
 fn main() {
-	Config::set( Config {
-		// inputs: ["DT", "DB", "DL", "DR"].iter().map(|&s| s.into()).collect(),
-		// inputs: ["X", "Y", "DB", "DT"].iter().map(|&s| s.into()).collect(),
-		inputs: ["DB", "DT"].iter().map(|&s| s.into()).collect(),
-		outputs: ["MOVX", "MOVY"].iter().map(|&s| s.into()).collect(),
-		neurons: 2,
-		strength_mult: 4.0, // multiplier for gene strengths
-		population: 20,
-		lifespan: 20,
-		genome_size: 4, // number of chromosomes
-		use_chromo: true, // multiple genes per functional chromosome?
-		independent: false, // do the creatures (not) interact with each other?
-		verbose: "silent".to_string(), // options: silent/low/high
-	});
-
-	let mut world :World<MyEnv, Blob> = World::new(); 
+	let config = Config::builder()
+		// .inputs(["DT", "DB", "DL", "DR"])
+		// .inputs(["X", "Y", "DB", "DT"])
+		.inputs(["DB", "DT"])
+		.outputs(["MOVX", "MOVY"])
+		.neurons(2)
+		.strength_mult(4.0) // multiplier for gene strengths
+		.population(20)
+		.lifespan(20)
+		.genome_size(4) // number of chromosomes
+		.use_chromo(true) // multiple genes per functional chromosome?
+		.independent(false) // do the creatures (not) interact with each other?
+		.verbose(Verbosity::Silent) // options: Silent/Low/High (sent to tracing)
+		.build()
+		.expect("invalid config");
+
+	let mut world :World<MyEnv, Blob> = World::new( config ); 
 	world.environs.print();
 
 	let generations = 10;
 	for i in 0..generations {
-		for j in 0..Config::get().lifespan {
+		for j in 0..world.config().lifespan {
 			let mut s = format!("{}", world.fitness_stats());
 			if i==0 && j==0 { s = format!("{} -- Press <Enter> to continue.", s); }
 			println!("{}", s);
@@ -118,8 +119,8 @@ impl Creature for Blob {
 		new
 	}
 
-	fn die(&self, age :usize, _fitness :f32, env :&mut Self::Env) -> bool { 
-		if age > Config::get().lifespan {
+	fn die(&self, age :usize, _fitness :f32, env :&mut Self::Env, cfg :&Config) -> bool { 
+		if age > cfg.lifespan {
 			env.map[ self.y ][ self.x ] = (0,0); // remove from the environment
 			true
 		} else {
@@ -0,0 +1,70 @@
+use gworld::{math, World, Config, Verbosity, Environs, TypedCreature, Sensors, Actions};
+
+// Same blobs as examples/blobs.rs, with the inputs and outputs as enums. 
+// A typo in a node name doesn't compile here, and the config's inputs/outputs come from the enums.
+
+fn main() {
+	let config = Config::builder()
+		.sensors::<Sense>() // inputs: X, Y
+		.actions::<Move>() // outputs: MOVX, MOVY
+		.neurons(3)
+		.population(50)
+		.lifespan(100)
+		.genome_size(6)
+		.verbose(Verbosity::Silent)
+		.build()
+		.expect("invalid config");
+
+	let mut world :World<MyEnv, Blob> = World::new( config ); 
+	world.advance( 1000 );
+	println!( "{}", world.fitness_stats() );
+}
+
+#[derive(Clone, Copy, Sensors)]
+enum Sense { X, Y }
+
+#[derive(Clone, Copy, Actions)]
+enum Move { 
+	#[gworld(name = "MOVX")] X, 
+	#[gworld(name = "MOVY")] Y,
+}
+
+struct MyEnv {}
+
+impl Environs for MyEnv {
+	type Creature = Blob;
+	fn new() -> Self { Self{} }
+}
+
+struct Blob {
+	x: f32,
+	y: f32,
+}
+
+impl TypedCreature for Blob {
+	type Env = MyEnv;
+	type Sensor = Sense;
+	type Action = Move;
+	
+	fn new( _env: &mut Self::Env, _parents: Vec<&Self> ) -> Self {
+		Self { x: 10., y: 10. }
+	}
+	
+	fn act( &mut self, _env: &mut Self::Env ) -> f32 {
+		f32::max( 0., 10. - self.y ) // fitness: get up to y=0
+	}
+	
+	fn rx_sensor( &self, sensor: Sense, _env: &Self::Env ) -> f32 {
+		match sensor { // no fallback arm needed, every sensor is covered
+			Sense::X => self.x,
+			Sense::Y => self.y,
+		}
+	}
+	
+	fn tx_action( &mut self, action: Move, value: f32, _env: &Self::Env ) {
+		match action {
+			Move::X => self.x += math::tanh( value ),
+			Move::Y => self.y += math::tanh( value ),
+		}
+	}
+}
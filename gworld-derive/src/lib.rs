@@ -0,0 +1,80 @@
+// #[derive(Sensors)] and #[derive(Actions)] for gworld. [See: gworld::TypedCreature]
+// Works on enums with unit variants. Each variant becomes an input (or output) node, named after the variant,
+// unless you say otherwise with #[gworld(name = "MOVX")]. The enum also needs Clone and Copy.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+#[proc_macro_derive(Sensors, attributes(gworld))]
+pub fn derive_sensors(input :TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand( &input, quote!(::gworld::Sensors) ).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+#[proc_macro_derive(Actions, attributes(gworld))]
+pub fn derive_actions(input :TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand( &input, quote!(::gworld::Actions) ).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(input :&DeriveInput, trait_path :TokenStream2) -> syn::Result<TokenStream2> {
+	let ident = &input.ident;
+	let data = match &input.data {
+		Data::Enum(data) => data,
+		_ => return Err( syn::Error::new_spanned( ident, "only enums can be Sensors or Actions" ) ),
+	};
+	if data.variants.is_empty() {
+		return Err( syn::Error::new_spanned( ident, "needs at least one variant" ) )
+	}
+
+	let mut names :Vec<String> = Vec::new();
+	let mut variants = Vec::new();
+	for variant in data.variants.iter() {
+		if !matches!(variant.fields, Fields::Unit) {
+			return Err( syn::Error::new_spanned( variant, "variants can't carry data, each one is a single node" ) )
+		}
+		let mut name = variant.ident.to_string();
+		for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("gworld")) {
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("name") {
+					name = meta.value()?.parse::<LitStr>()?.value();
+					Ok(())
+				} else {
+					Err( meta.error("expected `name = \"...\"`") )
+				}
+			})?;
+		}
+		if name.is_empty() {
+			return Err( syn::Error::new_spanned( variant, "node names can't be empty" ) )
+		}
+		if names.contains( &name ) {
+			return Err( syn::Error::new_spanned( variant, format!("duplicate node name \"{}\"", name) ) )
+		}
+		names.push( name );
+		variants.push( &variant.ident );
+	}
+
+	let indexes = 0..variants.len();
+	let indexes2 = indexes.clone();
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok( quote! {
+		impl #impl_generics #trait_path for #ident #ty_generics #where_clause {
+			const NAMES :&'static [&'static str] = &[ #( #names ),* ];
+
+			fn from_index(index :usize) -> Self {
+				match index {
+					#( #indexes => #ident::#variants, )*
+					_ => panic!( "{} has no node {}", stringify!(#ident), index ),
+				}
+			}
+
+			fn index(self) -> usize {
+				match self {
+					#( #ident::#variants => #indexes2, )*
+				}
+			}
+		}
+	})
+}
@@ -1,123 +1,380 @@
-use super::node::Node;
+use super::node::{Node, NodeType};
 use super::genes::{Gene, Genome};
-use super::config::Config;
-
-pub(crate) struct Nuron { // don't want to mispell in my code, so keep it phoenetic :P
-    pub(crate) state: f32, // current value
-    pub(crate) node: Node,
-    pub(crate) incoming: Vec<Gene>,
-}
-
-impl std::fmt::Display for Nuron {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut output = format!(" {}:", self.node);
-        for gene in &self.incoming { // so v.iter() and &v are the same thing! 
-            output += &format!("({},{:.1}),", gene.source, gene.strength );
-        }
-        write!(f, "{}", output)
-    }
-}
+use super::config::{Config, Evaluation};
+use super::math::Activation;
 
+// The active part of a genome, compiled for evaluation. 
+// Hidden neurons then outputs are the rows of a sparse (CSR) weight matrix: row r's incoming edges are
+// sources/weights[ offsets[r] .. offsets[r+1] ], with duplicate edges (same source and sink) merged into one.
 pub(crate) struct Brain {
 	pub(crate) node_state: Vec<f32>,
 	pub(crate) inputs: Vec<Node>,
-	pub(crate) nurons: Vec<Nuron>,
-	pub(crate) outputs: Vec<Nuron>,
+	pub(crate) nurons: Vec<Node>, // hidden rows (don't want to mispell in my code, so keep it phoenetic :P)
+	pub(crate) outputs: Vec<Node>, // output rows, after the hidden ones
+	pub(crate) input_mask: Vec<bool>, // per Config.inputs, is it wired in?
+	pub(crate) output_mask: Vec<bool>, // per Config.outputs
+	pub(crate) output_values: Vec<f32>, // per Config.outputs, 0 if not wired in
+	pub(crate) output_names: Vec<usize>, // index into Config.outputs, for each of outputs
+	pub(crate) offsets: Vec<usize>,
+	pub(crate) sources: Vec<usize>, // node_state index
+	pub(crate) weights: Vec<f32>,
+	pub(crate) recurrent: Vec<bool>, // per edge, closes a loop between hidden neurons: feedforward reads last tick's value
+	prev: Vec<f32>, // last tick's node_state, for those (feedforward only)
+	pub(crate) evaluation: Evaluation,
+	sums: Vec<f32>, // current value of each row, before activation
+	pub(crate) hidden_activation: Activation,
+	pub(crate) output_activation: Activation,
 }
 
 impl Brain { 
-	pub(crate) fn new( genome :&Genome ) -> Self {
-		// Find active neurons.
-		let mut nurons: Vec<Nuron> = Vec::new();
-		'find_nuron: for chro in genome.chromos.iter() {
-		  for gene in chro.genes.iter() {
-			// let's only look at sources (arbitrary, could have selective sinks) 
-			if gene.source.is_hidden() & gene.active {
-				// Ignore if it's already been added to nurons
-				for nuron in nurons.iter() { 
-					if nuron.node == gene.source {
-						continue 'find_nuron;
-					}
-				} 
-				
-				// Find all incoming nodes
-				let mut incoming: Vec<Gene> = Vec::new();
-				for c2 in genome.chromos.iter() {
-				  for g2 in c2.genes.iter() {
-					if g2.active & (g2.sink == gene.source) {
-						incoming.push( *g2 );
-					}
-				  }
-				}
-				
-				assert!( incoming.len() > 0 );
-				nurons.push( Nuron { node:gene.source, incoming:incoming, state:0f32 });
-			}
-		  }
-		}
+	pub(crate) fn new( genome :&Genome, cfg :&Config ) -> Self {
+		const NONE :usize = usize::MAX;
+		let genes = || genome.chromos.iter().flat_map(|chro| chro.genes.iter()).filter(|gene| gene.active);
+		let mut row = vec![NONE; cfg.node_count()]; // node idx -> row
 
-		// Find active inputs 
+		// Find active neurons and inputs. (only looking at sources for neurons, arbitrary, could have selective sinks) 
 		let mut inputs :Vec<Node> = Vec::new();
-		for chro in genome.chromos.iter() { // use iter, because sometimes you don't know/care-to-look-up if the current vector is & or not. More robust. :) 
-		  for gene in chro.genes.iter() {
-			if gene.source.is_input() & gene.active {
-				if !inputs.contains( &gene.source ) {
-					inputs.push( gene.source );
-				}
+		let mut nurons :Vec<Node> = Vec::new();
+		let mut seen = vec![false; cfg.node_count()];
+		for gene in genes() {
+			if seen[ gene.source.idx ] { continue }
+			seen[ gene.source.idx ] = true;
+			if gene.source.is_hidden() {
+				row[ gene.source.idx ] = nurons.len();
+				nurons.push( gene.source );
+			} else if gene.source.is_input() {
+				inputs.push( gene.source );
 			}
-		  }
 		}
-		
+
 		// Setup active outputs 
-		let mut outputs :Vec<Nuron> = Vec::new();
-		'find_output: for chro in genome.chromos.iter() {
-		  for gene in chro.genes.iter() {
-			if gene.sink.is_output() & gene.active {
-				let node = gene.sink; // & is not necessary here because Node is copyable, right? 
-				
-				// Ignore if it's already been added to outputs
-				for output in outputs.iter() { 
-					if output.node == node {
-						continue 'find_output;
-					}
-				} 
-				
-				// Find all incoming nodes
-				let mut incoming: Vec<Gene> = Vec::new();
-				for c2 in genome.chromos.iter() {
-				  for g2 in c2.genes.iter() {
-					if g2.active & (g2.sink == node) {
-						incoming.push( *g2 );
-					}
-				  }
-				}
-
-				assert!( incoming.len() > 0 );
-				outputs.push( Nuron { node:node, incoming:incoming, state:0f32 });
+		let mut outputs :Vec<Node> = Vec::new();
+		for gene in genes() {
+			if gene.sink.is_output() && row[ gene.sink.idx ] == NONE {
+				row[ gene.sink.idx ] = nurons.len() + outputs.len();
+				outputs.push( gene.sink );
+			}
+		}
+
+		// Find all incoming edges, one per source
+		let mut incoming :Vec<Vec<(usize, f32)>> = vec![Vec::new(); nurons.len() + outputs.len()];
+		for gene in genes() {
+			let r = row[ gene.sink.idx ];
+			if r == NONE { continue } // a hidden dead end, nobody listens to it
+			match incoming[r].iter_mut().find(|(source, _)| *source == gene.source.idx) {
+				Some(edge) => edge.1 += gene.strength,
+				None => incoming[r].push( (gene.source.idx, gene.strength) ),
 			}
-		  }
 		}
 
+		// Order the hidden neurons so everyone comes after the neurons they listen to (depth first, dependencies first).
+		// Starting from the outputs and working back, whatever closes a loop is marked recurrent, so the loops get cut
+		// on the far side from the inputs. Lagged doesn't care about any of this, everything reads last tick anyway.
+		let mut order = Vec::with_capacity( incoming.len() );
+		let mut visits = vec![Visit::New; nurons.len()];
+		let mut recurrent :Vec<Vec<bool>> = incoming.iter().map(|edges| vec![false; edges.len()]).collect();
+		let from_outputs = incoming[ nurons.len().. ].iter().flatten().map(|(source, _)| row[ *source ]);
+		let roots :Vec<usize> = from_outputs.filter(|r| *r < nurons.len()).chain( 0..nurons.len() ).collect();
+		for r in roots {
+			if visits[r] == Visit::New {
+				visit( r, &incoming, &row, &mut visits, &mut recurrent, &mut order );
+			}
+		}
+		let nurons :Vec<Node> = order.iter().map(|r| nurons[*r]).collect();
+		order.extend( nurons.len() .. incoming.len() ); // outputs last
+
+		let mut offsets = vec![0];
+		let (mut sources, mut weights, mut loops) = (Vec::new(), Vec::new(), Vec::new());
+		for r in order {
+			assert!( !incoming[r].is_empty() );
+			for ((source, weight), back) in incoming[r].iter().zip( recurrent[r].iter() ) {
+				sources.push( *source );
+				weights.push( *weight );
+				loops.push( *back );
+			}
+			offsets.push( sources.len() );
+		}
+		let prev = if cfg.evaluation == Evaluation::Feedforward && loops.contains(&true) { vec![0.; cfg.node_count()] } else { Vec::new() };
+
+		// names get looked up once, here, not every step. Input node idx is already the Config.inputs index.
+		let mut input_mask = vec![false; cfg.inputs.len()];
+		for node in inputs.iter() { input_mask[ node.idx ] = true; }
+		let output_names :Vec<usize> = outputs.iter().map(|node| node.idx - cfg.inputs.len() - cfg.neurons).collect();
+		let mut output_mask = vec![false; cfg.outputs.len()];
+		for name in output_names.iter() { output_mask[ *name ] = true; }
+
 		Self { // let brain =
+			input_mask, output_mask, output_names,
+			output_values: vec![0.; cfg.outputs.len()],
+			hidden_activation: cfg.hidden_activation,
+			output_activation: cfg.output_activation,
+			sums: vec![0.; nurons.len() + outputs.len()],
 			inputs, 
 			nurons, 
 			outputs, 
-			node_state: vec![0.; Config::get().node_count()],
+			offsets, sources, weights, prev,
+			recurrent: loops,
+			evaluation: cfg.evaluation,
+			node_state: vec![0.; cfg.node_count()],
 		}
 		// brain.print(); 
 		// return brain
 	}
 
+	// One tick. [See: Config.evaluation]
+	pub(crate) fn process(&mut self) {
+		match self.evaluation {
+			Evaluation::Lagged => self.lagged(),
+			Evaluation::Feedforward => self.feedforward(),
+		}
+
+		// outputs stay out of node_state (nothing reads them from there), and raw unless asked otherwise
+		for (name, sum) in self.output_names.iter().zip( self.sums[ self.nurons.len().. ].iter() ) {
+			self.output_values[ *name ] = self.output_activation.apply( *sum );
+		}
+	}
+
+	// every hidden and output row is summed from last tick's node_state (plus the fresh inputs of course),
+	// then the hidden ones get squashed and written back.
+	fn lagged(&mut self) {
+		for (r, sum) in self.sums.iter_mut().enumerate() {
+			let mut state = 0f32;
+			for e in self.offsets[r] .. self.offsets[r+1] {
+				state += self.node_state[ self.sources[e] ] * self.weights[e];
+			}
+			*sum = state;
+		}
+
+		for (node, sum) in self.nurons.iter().zip( self.sums.iter() ) {
+			self.node_state[ node.idx ] = self.hidden_activation.apply( *sum ); // squash them down! (tanh by default)
+		}
+	}
+
+	// rows in order, each hidden neuron written back as soon as it's summed, so inputs reach the outputs in one tick.
+	// Recurrent edges still read last tick's value.
+	fn feedforward(&mut self) {
+		if !self.prev.is_empty() {
+			self.prev.copy_from_slice( &self.node_state );
+		}
+		for r in 0..self.sums.len() {
+			let mut state = 0f32;
+			for e in self.offsets[r] .. self.offsets[r+1] {
+				let value = if self.recurrent[e] { self.prev[ self.sources[e] ] } else { self.node_state[ self.sources[e] ] };
+				state += value * self.weights[e];
+			}
+			self.sums[r] = state;
+			if r < self.nurons.len() {
+				self.node_state[ self.nurons[r].idx ] = self.hidden_activation.apply( state );
+			}
+		}
+	}
+
+	// everything back to zero, like it was just built
+	pub(crate) fn reset(&mut self) {
+		for state in [&mut self.node_state, &mut self.prev, &mut self.sums, &mut self.output_values] {
+			state.fill( 0. );
+		}
+	}
+
+	// active nodes
+	pub(crate) fn size(&self) -> usize {
+		self.inputs.len() + self.nurons.len() + self.outputs.len()
+	}
+
+	// (source, weight) going into row r
+	fn incoming(&self, r :usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+		let edges = self.offsets[r] .. self.offsets[r+1];
+		self.sources[ edges.clone() ].iter().copied().zip( self.weights[ edges ].iter().copied() )
+	}
+
+	// Graphviz, for looking at what evolved: `dot -Tsvg brain.dot > brain.svg`
+	// Edges are labeled with their (merged) weight, green for positive and red for negative, thicker when stronger.
+	// Any genes passed in as inactive show up dashed and grey, along with nodes only they touch.
+	pub(crate) fn to_dot( &self, cfg :&Config, inactive :&[Gene] ) -> String {
+		let mut active = vec![false; cfg.node_count()];
+		for node in self.inputs.iter().chain( self.nurons.iter() ).chain( self.outputs.iter() ) {
+			active[ node.idx ] = true;
+		}
+		let mut shown = active.clone();
+		for gene in inactive.iter() {
+			shown[ gene.source.idx ] = true;
+			shown[ gene.sink.idx ] = true;
+		}
+
+		let mut dot = String::from("digraph brain {\n\trankdir=LR;\n\tnode [fontname=\"Helvetica\"];\n");
+		let layers = [
+			(NodeType::INPUT, "inputs", "box"), 
+			(NodeType::HIDDEN, "hidden", "circle"), 
+			(NodeType::OUTPUT, "outputs", "doublecircle"),
+		];
+		for (node_type, title, shape) in layers {
+			dot += &format!("\tsubgraph cluster_{} {{\n\t\tlabel=\"{}\";\n\t\tcolor=lightgrey;\n", title, title);
+			for idx in (0..cfg.node_count()).filter(|idx| shown[*idx]) {
+				let node = node_at( idx, cfg );
+				if node.node_type != node_type { continue }
+				let style = if active[idx] { "" } else { ", style=dashed, color=grey" };
+				dot += &format!("\t\tn{} [label={:?}, shape={}{}];\n", idx, dot_label( &node, cfg ), shape, style);
+			}
+			dot += "\t}\n";
+		}
+
+		for (r, node) in self.nurons.iter().chain( self.outputs.iter() ).enumerate() {
+			for (source, weight) in self.incoming(r) {
+				let color = if weight < 0. { "firebrick" } else { "forestgreen" };
+				dot += &format!("\tn{} -> n{} [label=\"{:.2}\", color={}, penwidth={:.2}];\n", 
+					source, node.idx, weight, color, 0.5 + weight.abs().min( cfg.strength_mult ));
+			}
+		}
+		for gene in inactive.iter() {
+			dot += &format!("\tn{} -> n{} [label=\"{:.2}\", color=grey, style=dashed];\n", gene.source.idx, gene.sink.idx, gene.strength);
+		}
+		dot += "}\n";
+		dot
+	}
+
 	#[allow(dead_code)]
 	fn print(&self) {
 		println!("Inputs: {:?}", &self.inputs);		
-		print_nurons( "Neurons", &self.nurons );
-		print_nurons( "Outputs", &self.outputs );		
-		fn print_nurons( title: &str, nurons: &Vec<Nuron> ) {
-			print!("{}:", title);
-			for nuron in nurons.iter() { print!("{}", nuron); }
-			println!(" END");
+		let rows = self.nurons.iter().chain( self.outputs.iter() ).enumerate();
+		for (r, node) in rows {
+			if r == 0 { print!("Neurons:"); }
+			if r == self.nurons.len() { print!(" END\nOutputs:"); }
+			print!(" {}:", node);
+			for (source, weight) in self.incoming(r) { print!("({},{:.1}),", source, weight); }
 		}
+		println!(" END");
 	}	
 
 }
+
+// what node lives at a node_state index
+fn node_at( idx :usize, cfg :&Config ) -> Node {
+	let node_type = if idx < cfg.inputs.len() { NodeType::INPUT } 
+		else if idx < cfg.inputs.len() + cfg.neurons { NodeType::HIDDEN } 
+		else { NodeType::OUTPUT };
+	Node { idx, node_type }
+}
+
+// config names for inputs and outputs, hidden neurons as everywhere else (H2 is node 2) [See: Node's Display]
+fn dot_label( node :&Node, cfg :&Config ) -> String {
+	match node.node_type {
+		NodeType::INPUT => cfg.inputs[ node.idx ].clone(),
+		NodeType::HIDDEN => node.to_string(),
+		NodeType::OUTPUT => cfg.outputs[ node.idx - cfg.inputs.len() - cfg.neurons ].clone(),
+	}
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Visit { New, Open, Done }
+
+// depth first over the hidden rows, through their incoming edges
+fn visit( r :usize, incoming :&[Vec<(usize, f32)>], row :&[usize], visits :&mut [Visit], recurrent :&mut [Vec<bool>], order :&mut Vec<usize> ) {
+	visits[r] = Visit::Open;
+	for (e, (source, _)) in incoming[r].iter().enumerate() {
+		let from = row[ *source ];
+		if from >= visits.len() { continue } // inputs
+		match visits[from] {
+			Visit::Open => recurrent[r][e] = true, // it's waiting on us, a loop
+			Visit::New => visit( from, incoming, row, visits, recurrent, order ),
+			Visit::Done => (),
+		}
+	}
+	visits[r] = Visit::Done;
+	order.push( r );
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::math;
+	use crate::world::tests::config;
+
+	#[test]
+	fn merges_duplicate_edges() {
+		let cfg = config(); // inputs X, ONE, neurons 3, output MOVX: nodes I0 I1 H2 H3 H4 O5
+		// X -> H2 (2.0), H2 -> MOVX (2.0), H2 -> MOVX again (1.0)
+		let genome = Genome::from_hex( "gw1:008040008000400080002000", &cfg ).unwrap();
+		let mut brain = Brain::new( &genome, &cfg );
+		assert_eq!( (brain.inputs.len(), brain.nurons.len(), brain.outputs.len()), (1, 1, 1) );
+		assert_eq!( (brain.offsets.clone(), brain.sources.clone()), (vec![0, 1, 2], vec![0, 2]) );
+		assert!( (brain.weights[1] - 3.0).abs() < 1e-3 );
+
+		brain.node_state[0] = 1.;
+		brain.process(); // the output still sees last tick's hidden state
+		assert_eq!( brain.output_values[0], 0. );
+		brain.process();
+		let expect = math::tanh( brain.weights[0] ) * brain.weights[1];
+		assert!( (brain.output_values[0] - expect).abs() < 1e-6 );
+
+		let cfg = Config { hidden_activation: Activation::Relu, output_activation: Activation::Sigmoid, ..cfg };
+		let mut brain = Brain::new( &genome, &cfg );
+		brain.node_state[0] = -1.; // relu kills it
+		brain.process();
+		brain.process();
+		assert_eq!( brain.output_values[0], 0.5 );
+	}
+
+	// The old builder skipped the rest of a chromosome after a repeated source, so H3 here was never built
+	// (and MOVX only ever heard from H2). Every active source gets its neuron now.
+	#[test]
+	fn builds_neurons_after_a_repeat() {
+		let cfg = config();
+		// X -> H2, H2 -> MOVX, H2 -> H3 (H2 again), H3 -> MOVX
+		let genome = Genome::from_hex( "gw1:00804000800040008081400081004000", &cfg ).unwrap();
+		let brain = Brain::new( &genome, &cfg );
+		let mut hidden :Vec<usize> = brain.nurons.iter().map(|n| n.idx).collect();
+		hidden.sort();
+		assert_eq!( hidden, vec![2, 3] );
+		let output = brain.nurons.len();
+		let mut sources = brain.sources[ brain.offsets[output] .. brain.offsets[output+1] ].to_vec();
+		sources.sort();
+		assert_eq!( sources, vec![2, 3] );
+	}
+
+	#[test]
+	fn feedforward_in_one_tick() {
+		// X -> H2 -> H3 -> MOVX, and H3 -> H2 closing a loop
+		let hex = "gw1:00804000808140008100400081802000";
+		let (w, w2) = (0x4000 as f32 / 0x7fff as f32 * 4., 0x2000 as f32 / 0x7fff as f32 * 4.);
+
+		let cfg = config();
+		let mut brain = Brain::new( &Genome::from_hex( hex, &cfg ).unwrap(), &cfg );
+		brain.node_state[0] = 1.;
+		brain.process();
+		assert_eq!( brain.output_values[0], 0. ); // lagged: still on its way
+
+		let cfg = Config { evaluation: Evaluation::Feedforward, ..config() };
+		let mut brain = Brain::new( &Genome::from_hex( hex, &cfg ).unwrap(), &cfg );
+		assert_eq!( brain.nurons.iter().map(|n| n.idx).collect::<Vec<_>>(), vec![2, 3] ); // H2 before H3
+		assert_eq!( brain.recurrent.iter().filter(|r| **r).count(), 1 );
+		brain.node_state[0] = 1.;
+		brain.process();
+		let h3 = math::tanh( w * math::tanh( w ) ); // H2 heard nothing from H3 yet
+		assert!( (brain.output_values[0] - w * h3).abs() < 1e-5 );
+		brain.process();
+		let h3 = math::tanh( w * math::tanh( w + w2 * h3 ) ); // now it has, from last tick
+		assert!( (brain.output_values[0] - w * h3).abs() < 1e-5 );
+	}
+
+	#[test]
+	fn dot_graph() {
+		let cfg = config();
+		// X -> H2 -> H3 -> MOVX, H3 -> H2, and ONE -> H4 going nowhere
+		let genome = Genome::from_hex( "gw1:0080400080814000810040008180200001828000", &cfg ).unwrap();
+		let brain = Brain::new( &genome, &cfg );
+		let inactive :Vec<Gene> = genome.inactive_genes().collect();
+		assert_eq!( inactive.len(), 1 );
+
+		let dot = brain.to_dot( &cfg, &[] );
+		assert!( dot.starts_with("digraph brain {") && dot.ends_with("}\n") );
+		assert!( dot.contains("n0 [label=\"X\", shape=box];") );
+		assert!( dot.contains("n2 [label=\"H2\", shape=circle];") );
+		assert!( dot.contains("n5 [label=\"MOVX\", shape=doublecircle];") );
+		assert!( dot.contains("n3 -> n2 [label=\"1.00\", color=forestgreen") );
+		assert!( !dot.contains("n1") );
+
+		let dot = brain.to_dot( &cfg, &inactive );
+		assert!( dot.contains("n1 [label=\"ONE\", shape=box, style=dashed, color=grey];") );
+		assert!( dot.contains("n1 -> n4 [label=\"-4.00\", color=grey, style=dashed];") );
+	}
+}
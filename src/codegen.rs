@@ -0,0 +1,257 @@
+use super::brains::Brain;
+use super::config::Evaluation;
+use super::math::Activation;
+use std::fmt::Write;
+
+// An evolved brain as plain Rust source: one module, weights baked in as literals, state in fixed-size arrays,
+// no allocation and no dependency on gworld. [See: Network::to_rust]
+// Same sums in the same order with the same formulas as Brain::process, so the results match to the bit.
+// Except with no_std, where exp and sin come from libm instead of std and can be off in the last bit (std calls the
+// platform's math library, which libm doesn't promise to agree with).
+pub(crate) fn to_rust( brain :&Brain, think_ticks :usize, inputs :&[String], outputs :&[String], module :&str, no_std :bool ) -> Result<String, CodegenError> {
+	check_module( module )?;
+	let mut out = String::new();
+	// writing to a String can't fail, so the unwraps below are fine
+	let feedforward = brain.evaluation == Evaluation::Feedforward;
+	let looped = feedforward && brain.recurrent.contains(&true);
+	let hidden = brain.nurons.len();
+
+	writeln!( out, "// Generated by gworld from an evolved genome, don't edit (regenerate it instead)." ).unwrap();
+	if no_std { writeln!( out, "// no_std, needs the libm crate." ).unwrap(); }
+	writeln!( out, "#[allow(unused, clippy::all)]" ).unwrap();
+	writeln!( out, "pub mod {} {{", module ).unwrap();
+	writeln!( out, "\tpub const INPUTS :usize = {};", inputs.len() ).unwrap();
+	writeln!( out, "\tpub const OUTPUTS :usize = {};", outputs.len() ).unwrap();
+	writeln!( out, "\tpub const INPUT_NAMES :[&str; INPUTS] = {:?};", inputs ).unwrap();
+	writeln!( out, "\tpub const OUTPUT_NAMES :[&str; OUTPUTS] = {:?};", outputs ).unwrap();
+	writeln!( out, "\tconst NODES :usize = {};", brain.node_state.len() ).unwrap();
+	writeln!( out, "\tconst THINK_TICKS :usize = {};", think_ticks ).unwrap();
+	out += "
+	pub struct Brain {
+		state :[f32; NODES], // inputs, then hidden (outputs are never read back)
+		outputs :[f32; OUTPUTS],
+	}
+
+	impl Brain {
+		pub const fn new() -> Self {
+			Self { state: [0.; NODES], outputs: [0.; OUTPUTS] }
+		}
+
+		// back to a newborn's blank state
+		pub fn reset(&mut self) {
+			*self = Self::new();
+		}
+
+		// One step. inputs in INPUT_NAMES order, outputs come back in OUTPUT_NAMES order.
+		pub fn step(&mut self, inputs :&[f32; INPUTS]) -> &[f32; OUTPUTS] {
+			self.state[ ..INPUTS ].copy_from_slice( inputs );
+			for _tick in 0..THINK_TICKS {
+				self.tick();
+			}
+			&self.outputs
+		}
+
+		fn tick(&mut self) {
+			let s = &mut self.state;
+";
+	if looped { out += "\t\t\tlet prev = *s;\n"; }
+	for r in 0 .. hidden + brain.outputs.len() {
+		let mut sum = String::from("0f32");
+		for e in brain.offsets[r] .. brain.offsets[r+1] {
+			let from = if looped && brain.recurrent[e] { "prev" } else { "s" };
+			write!( sum, " + {}[{}] * {:?}_f32", from, brain.sources[e], brain.weights[e] ).unwrap(); // Debug round trips an f32 exactly
+		}
+		writeln!( out, "\t\t\tlet r{} = {};", r, sum ).unwrap();
+		if feedforward && r < hidden { // written back right away, lagged waits for every row
+			writeln!( out, "\t\t\ts[{}] = {};", brain.nurons[r].idx, call( brain.hidden_activation, r ) ).unwrap();
+		}
+	}
+	if !feedforward {
+		for (r, node) in brain.nurons.iter().enumerate() {
+			writeln!( out, "\t\t\ts[{}] = {};", node.idx, call( brain.hidden_activation, r ) ).unwrap();
+		}
+	}
+	for (i, name) in brain.output_names.iter().enumerate() {
+		writeln!( out, "\t\t\tself.outputs[{}] = {};", name, call( brain.output_activation, hidden + i ) ).unwrap();
+	}
+	out += "\t\t}\n\t}\n";
+
+	// only the ones this brain uses, same formulas as gworld::math
+	let (exp, sin) = if no_std { ("libm::expf", "libm::sinf") } else { ("f32::exp", "f32::sin") };
+	let mut used = vec![ brain.hidden_activation ];
+	if brain.output_activation != brain.hidden_activation { used.push( brain.output_activation ); }
+	for activation in used {
+		let body = match activation {
+			Activation::Identity => continue,
+			Activation::Tanh => format!("let e1 = {exp}(x);\n\t\tlet e2 = {exp}(-x);\n\t\t(e1 - e2) / (e1 + e2)"),
+			Activation::Sigmoid => format!("1.0 / (1.0 + {exp}(-x))"),
+			Activation::Relu => "f32::max(0.0, x)".to_string(),
+			Activation::LeakyRelu => "if x > 0.0 { x } else { 0.01 * x }".to_string(),
+			Activation::Step => "if x > 0.0 { 1.0 } else { 0.0 }".to_string(),
+			Activation::Gaussian => format!("{exp}(-x * x)"),
+			Activation::Sine => format!("{sin}(x)"),
+		};
+		write!( out, "\n\tfn {}(x :f32) -> f32 {{\n\t\t{}\n\t}}\n", name( activation ), body ).unwrap();
+	}
+	out += "}\n";
+	Ok( out )
+}
+
+// The module name goes straight into `pub mod <name>`, so it has to be a plain identifier
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodegenError {
+	ModuleName(String),
+}
+
+impl std::fmt::Display for CodegenError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			CodegenError::ModuleName(name) => write!(f, "`{}` isn't a valid Rust module name", name),
+		}
+	}
+}
+
+impl std::error::Error for CodegenError {}
+
+const KEYWORDS :[&str; 52] = [
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+	"if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+	"struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do",
+	"final", "gen", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+fn check_module( name :&str ) -> Result<(), CodegenError> {
+	let mut chars = name.chars();
+	let starts = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+	if !starts || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') || name == "_" || KEYWORDS.contains(&name) {
+		return Err( CodegenError::ModuleName( name.to_string() ) )
+	}
+	Ok(())
+}
+
+fn call( activation :Activation, r :usize ) -> String {
+	match activation {
+		Activation::Identity => format!("r{}", r),
+		_ => format!("{}(r{})", name( activation ), r),
+	}
+}
+
+fn name( activation :Activation ) -> &'static str {
+	match activation {
+		Activation::Identity => "identity",
+		Activation::Tanh => "tanh",
+		Activation::Sigmoid => "sigmoid",
+		Activation::Relu => "relu",
+		Activation::LeakyRelu => "leaky_relu",
+		Activation::Step => "step",
+		Activation::Gaussian => "gaussian",
+		Activation::Sine => "sine",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Network, Config, Evaluation, Verbosity};
+	use crate::math::Activation;
+	use rand::{Rng, SeedableRng};
+	use rand_chacha::ChaCha8Rng;
+
+	// checked in, so they get compiled (without gworld in scope) and run against the interpreter below
+	include!( concat!( env!("CARGO_MANIFEST_DIR"), "/testdata/lagged_brain.rs" ) ); // pub mod lagged
+	include!( concat!( env!("CARGO_MANIFEST_DIR"), "/testdata/feedforward_brain.rs" ) ); // pub mod feedforward
+	include!( concat!( env!("CARGO_MANIFEST_DIR"), "/testdata/embedded_brain.rs" ) ); // pub mod embedded, no_std (also built on its own by testdata/no_std)
+
+	fn lagged_net() -> Network {
+		let cfg = Config::builder()
+			.inputs(["X", "Y", "ONE"])
+			.outputs(["MOVX", "MOVY", "EAT"])
+			.neurons(4)
+			.verbose(Verbosity::Silent)
+			.build()
+			.unwrap();
+		let hex = "gw1:1bbce95d9f7acbb4a391f99c14d03022ea7442b452a36454,cca889f0156948cc,c7c9f4559892d9484c6ba034,\
+			cf48e5974fda3c11344e34fa,af6905e43810d4dc,9234ffc44987bed1c68b5a333fd094feb4374420,ceeac52d40de5651ed011fae765e7c28,6b55824c";
+		Network::from_hex( &hex.replace( char::is_whitespace, "" ), &cfg ).unwrap()
+	}
+
+	fn feedforward_net_config() -> Config {
+		Config {
+			evaluation: Evaluation::Feedforward,
+			think_ticks: 2,
+			hidden_activation: Activation::Sigmoid,
+			output_activation: Activation::LeakyRelu,
+			..crate::world::tests::config()
+		}
+	}
+
+	fn feedforward_net() -> Network {
+		// X -> H2 -> H3 -> MOVX, H3 -> H2 closing a loop, and ONE -> H4 going nowhere
+		Network::from_hex( "gw1:0080400080814000810040008180200001828000", &feedforward_net_config() ).unwrap()
+	}
+
+	// exp and sin both, from libm
+	fn embedded_net() -> Network {
+		let cfg = Config { hidden_activation: Activation::Tanh, output_activation: Activation::Sine, ..feedforward_net_config() };
+		Network::from_hex( "gw1:0080400080814000810040008180200001828000", &cfg ).unwrap()
+	}
+
+	fn fixture(name :&str) -> String {
+		std::fs::read_to_string( format!("{}/testdata/{}_brain.rs", env!("CARGO_MANIFEST_DIR"), name) ).unwrap()
+	}
+
+	#[test]
+	fn generates_the_fixtures() {
+		// if the generator changes on purpose, write to_rust's output over these
+		assert_eq!( lagged_net().to_rust("lagged").unwrap(), fixture("lagged") );
+		assert_eq!( feedforward_net().to_rust("feedforward").unwrap(), fixture("feedforward") );
+		assert_eq!( embedded_net().to_rust_no_std("embedded").unwrap(), fixture("embedded") );
+	}
+
+	#[test]
+	fn matches_the_network() {
+		let mut rng = ChaCha8Rng::seed_from_u64( 11 );
+		let bits = |outputs :&[f32]| outputs.iter().map(|x| x.to_bits()).collect::<Vec<u32>>(); // exactly the same
+		let (mut net, mut brain) = (lagged_net(), lagged::Brain::new());
+		for step in 0..20 {
+			if step == 10 {
+				net.reset();
+				brain.reset();
+			}
+			let inputs :[f32; lagged::INPUTS] = [rng.gen_range(-2. .. 2.), rng.gen_range(-2. .. 2.), 1.];
+			assert_eq!( bits( brain.step( &inputs ) ), bits( net.step( &inputs ) ) );
+		}
+
+		let (mut net, mut brain) = (feedforward_net(), feedforward::Brain::new());
+		for _step in 0..20 {
+			let inputs :[f32; feedforward::INPUTS] = [rng.gen_range(-2. .. 2.), 1.];
+			assert_eq!( bits( brain.step( &inputs ) ), bits( net.step( &inputs ) ) );
+		}
+		assert_eq!( feedforward::OUTPUT_NAMES, ["MOVX"] );
+
+		// libm may round exp and sin differently than std, so only close
+		let (mut net, mut brain) = (embedded_net(), embedded::Brain::new());
+		for _step in 0..20 {
+			let inputs :[f32; embedded::INPUTS] = [rng.gen_range(-2. .. 2.), 1.];
+			let (a, b) = (brain.step( &inputs )[0], net.step( &inputs )[0]);
+			assert!( (a - b).abs() <= 1e-6, "{} vs {}", a, b );
+		}
+	}
+
+	#[test]
+	fn no_std() {
+		let code = feedforward_net().to_rust_no_std("brain").unwrap();
+		assert!( code.contains("libm::expf(-x)") );
+		assert!( !code.contains("f32::exp") );
+	}
+
+	#[test]
+	fn checks_the_module_name() {
+		let net = lagged_net();
+		for name in ["brain", "_brain", "Brain2", "champion_1"] {
+			assert!( net.to_rust( name ).is_ok(), "{}", name );
+		}
+		for name in ["", "_", "2brain", "my brain", "brain-1", "brain { }", "mod", "self", "fn", "brüin"] {
+			assert_eq!( net.to_rust_no_std( name ), Err( crate::CodegenError::ModuleName( name.to_string() ) ) );
+		}
+	}
+}
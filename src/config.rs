@@ -1,8 +1,17 @@
-use super::node::Node;
+use super::genes::{Crossover, Dominance};
+use super::world::Reseed;
+use super::typed::{Sensors, Actions};
+use super::math::Activation;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::path::Path;
 
-#[derive(Clone)]
+// Each World owns its own Config (no more global!), and passes a reference down to
+// the genome, brains, and organisms as needed. So multiple worlds can live side by side. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)] // files only need the fields they care about
 pub struct Config {
-	pub verbose :String, // "low", "high", or _ ;  could use enum... but easier for user this way.
+	pub verbose :Verbosity, // how chatty the library's tracing events are
 	pub population :usize,
 	pub lifespan: usize,
 	pub genome_size :usize, 
@@ -13,92 +22,349 @@ pub struct Config {
 	pub inputs :Vec<String>,
 	pub outputs :Vec<String>,
 	pub neurons :usize,
+	pub hidden_activation :Activation, // squashing for the hidden neurons
+	pub output_activation :Activation, // and the outputs (identity passes the raw sum along)
+	pub evaluation :Evaluation, // how a signal travels through the brain each step
+	pub think_ticks :usize, // brain propagations per step, between sensing and acting
+	pub parents :usize, // per baby. 1 is budding (asexual), 2+ breeds with crossover
+	pub crossover :Crossover, // how the parents' chromosomes get mixed
+	pub diploid :bool, // two homologous sets of chromosomes per organism, instead of one
+	pub dominance :Dominance, // which homolog gets expressed, when diploid
+	pub min_population :usize, // population floor, anything below gets topped up by reseed
+	pub reseed :Reseed, // where the top up comes from (also used when everything dies)
+	pub hall_of_fame :usize, // how many of the best-ever genomes to keep around (0 for none)
+	pub seed :Option<u64>, // same seed + same config = same run. None picks one at random (see World::seed)
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self { 
+			verbose: Verbosity::Low,
+			population: 100,
+			lifespan: 50,
+			genome_size: 50,
+			use_chromo: true,
+			independent: false,
+			strength_mult: 4.0, // multiplier for gene strengths
+			inputs: Vec::new(), // no sensible default here, the user has to name them
+			outputs: Vec::new(),
+			neurons: 5,
+			hidden_activation: Activation::Tanh,
+			output_activation: Activation::Identity,
+			evaluation: Evaluation::Lagged,
+			think_ticks: 1,
+			parents: 1,
+			crossover: Crossover::Uniform,
+			diploid: false,
+			dominance: Dominance::Stronger,
+			min_population: 0,
+			reseed: Reseed::Random,
+			hall_of_fame: 10,
+			seed: None,
+		}
+	}
 }
 
 impl Config {
-	pub fn set( cfg :Self ) {
-	 	{
-	 		let mut guard = CONFIG.lock().unwrap();
-	 		*guard = cfg; // this actually work?! Ugh, this is ugly. Whatever. 
-	 	} // I think a block is need to release the lock? (Lolol)
+	// Only spell out what you care about, the rest are defaults. 
+	// Config::builder().inputs(["X", "Y"]).outputs(["MOVX"]).build()?
+	pub fn builder() -> ConfigBuilder {
+		ConfigBuilder::default()
+	}
+
+	// Catch the bad values here, rather than as a panic deep inside the genome (modulo by zero and friends)
+	pub fn validate(&self) -> Result<(), ConfigError> {
+		check_names( "inputs", &self.inputs )?;
+		check_names( "outputs", &self.outputs )?;
+		check_count( "neurons", self.neurons )?;
+		check_nodes( "neurons", self.neurons )?;
+		check_count( "population", self.population )?;
+		check_count( "lifespan", self.lifespan )?;
+		check_count( "genome_size", self.genome_size )?;
+		check_count( "parents", self.parents )?;
+		check_count( "think_ticks", self.think_ticks )?;
+		if self.crossover == Crossover::NPoint(0) {
+			return Err( ConfigError::Zero("crossover") );
+		}
+		if self.min_population > self.population {
+			return Err( ConfigError::Invalid { field: "min_population", reason: format!("{} is more than the population ({})", self.min_population, self.population) } );
+		}
+		if !self.strength_mult.is_finite() {
+			return Err( ConfigError::Invalid { field: "strength_mult", reason: format!("{} is not a finite number", self.strength_mult) } );
+		}
+		Ok(())
+	}
+
+	// Reads a .toml or .json file (judged by extension), then applies key=value overrides on top. 
+	// ie: Config::load( "experiment.toml", std::env::args().skip(1) )
+	pub fn load<P :AsRef<Path>, I :IntoIterator<Item = S>, S :AsRef<str>>(path :P, overrides :I) -> Result<Config, ConfigError> {
+		let mut config = Config::from_file( path )?;
+		config.apply_overrides( overrides )?;
+		Ok( config )
+	}
+
+	pub fn from_file<P :AsRef<Path>>(path :P) -> Result<Config, ConfigError> {
+		let path = path.as_ref();
+		let text = std::fs::read_to_string( path )
+			.map_err(|e| ConfigError::Io { path: path.display().to_string(), reason: e.to_string() })?;
+		let value :Value = match path.extension().and_then(|ext| ext.to_str()) {
+			Some("json") => serde_json::from_str( &text ).map_err(|e| ConfigError::Parse(e.to_string()))?,
+			_ => toml::from_str( &text ).map_err(|e| ConfigError::Parse(e.to_string()))?, // toml unless told otherwise
+		};
+		Config::from_value( value )
+	}
+
+	// Overrides look like "population=200", lists are comma separated: "inputs=X,Y,DT"
+	pub fn apply_overrides<I :IntoIterator<Item = S>, S :AsRef<str>>(&mut self, overrides :I) -> Result<(), ConfigError> {
+		let mut fields = match serde_json::to_value( &*self ) {
+			Ok( Value::Object(fields) ) => fields,
+			_ => unreachable!("Config always serializes to a map"),
+		};
+		for arg in overrides {
+			let arg = arg.as_ref();
+			let (key, raw) = arg.split_once('=').ok_or_else(|| ConfigError::InvalidOverride(arg.to_string()))?;
+			let key = key.trim();
+			let raw = raw.trim();
+			let value = match fields.get( key ) {
+				None => return Err( ConfigError::UnknownKey(key.to_string()) ),
+				Some( Value::Array(_) ) => Value::Array( 
+					raw.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).map(|s| Value::String(s.to_string())).collect() 
+				),
+				// json if it parses (numbers, bools, crossover={"npoint":3}), a plain string otherwise (verbose=high, crossover=uniform)
+				Some(_) => serde_json::from_str( raw ).unwrap_or_else(|_| Value::String( raw.to_string() )),
+			};
+			fields.insert( key.to_string(), value );
+		}
+		*self = Config::from_value( Value::Object(fields) )?;
+		Ok(())
 	}
 
-	// Something just seems wrong here... whatever. I hate this BS myself. But what can you do other than pass around config everywhere?? (which I'm not going to do.)
-	pub fn get() -> Config { 
-		(*CONFIG.lock().unwrap()).clone()
+	fn from_value(value :Value) -> Result<Config, ConfigError> {
+		if let Value::Object(fields) = &value { // name the unknown key, serde would just list what it expected
+			let known = serde_json::to_value( Config::default() ).unwrap_or(Value::Null);
+			if let Some(key) = fields.keys().find(|key| known.get(key.as_str()).is_none()) {
+				return Err( ConfigError::UnknownKey(key.to_string()) );
+			}
+		}
+		let config :Config = serde_json::from_value( value ).map_err(|e| ConfigError::Parse(e.to_string()))?;
+		config.validate()?;
+		Ok( config )
 	}
 
 	pub(crate) fn node_count(&self) -> usize {
 		self.inputs.len() + self.neurons + self.outputs.len()
 	}
 
-	pub(crate) fn node_name(&self, node :&Node) -> String {
-		if node.idx < self.inputs.len() {
-			format!("{}", &self.inputs[ node.idx ])
-		} else if node.idx >= (self.inputs.len() + self.neurons) {
-			assert!( node.idx < (self.inputs.len() + self.neurons + self.outputs.len()) );
-			format!("{}", &self.outputs[ node.idx - (self.neurons + self.inputs.len()) ])
-		} else {
-			String::from("??")
+	pub(crate) fn log(&self, level :Verbosity) -> bool {
+		self.verbose >= level // high includes low
+	}
+}
+
+// Nothing gets printed by the library. These just decide which events get sent to tracing,
+// hook up a subscriber (ie tracing_subscriber::fmt::init()) to actually see them. 
+// In a file, or as an override, they're spelled lowercase: verbose = "high"
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+	Silent, // no events at all
+	Low, // a fitness summary per batch of steps
+	High, // plus the winners of every offspring lottery (these printed at "low" back when it was println)
+}
+
+// Lagged: every neuron reads last step's values, so a signal moves one layer per step (the original behaviour).
+// Feedforward: neurons are evaluated in dependency order, so inputs reach the outputs in the same step. 
+// Loops between hidden neurons can't be ordered, those edges still read last step's value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Evaluation {
+	Lagged,
+	Feedforward,
+}
+
+// A gene stores its node index in 7 bits (the 8th flags a hidden node), so that's all the nodes we can address per layer
+pub const MAX_NODES :usize = 0x80;
+
+fn check_count(field :&'static str, count :usize) -> Result<(), ConfigError> {
+	if count == 0 {
+		Err( ConfigError::Zero(field) )
+	} else {
+		Ok(())
+	}
+}
+
+fn check_names(field :&'static str, names :&[String]) -> Result<(), ConfigError> {
+	if names.is_empty() {
+		return Err( ConfigError::Empty(field) );
+	}
+	check_nodes( field, names.len() )?;
+	for (i, name) in names.iter().enumerate() {
+		if names[..i].contains( name ) { // rx_input/tx_output would never be able to tell them apart
+			return Err( ConfigError::Invalid { field, reason: format!("duplicate name \"{}\"", name) } );
 		}
 	}
+	Ok(())
+}
 
-	pub(crate) fn log(s :&str) -> bool {
-		Config::get().verbosity( s )
-	}
-
-	fn verbosity(&self, s :&str) -> bool {
-		let v = self.verbose.to_lowercase();
-        match s.to_lowercase().as_str() {
- 			"on" => 
- 				match v.as_str() {
- 					"silent" => false,
- 					_ => true,
- 				},
-             "low" => // both low and high qualify
- 				match v.as_str() {
- 					"low" => true,
- 					"high" => true,
- 					_ => false,
- 				},
- 			"high" => // only high applies
- 				match v.as_str() {
- 					"high" => true,
- 					_ => false,
- 				},
- 			_ => false,
- 		}
+fn check_nodes(field :&'static str, count :usize) -> Result<(), ConfigError> {
+	if count > MAX_NODES {
+		Err( ConfigError::TooManyNodes { field, count } )
+	} else {
+		Ok(())
 	}
+}
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+	Empty(&'static str), // field needs at least one entry
+	Zero(&'static str), // field must be greater than zero
+	TooManyNodes { field :&'static str, count :usize }, // more than MAX_NODES
+	Invalid { field :&'static str, reason :String },
+	UnknownKey(String), // from a file or an override
+	InvalidOverride(String), // not key=value, or the value doesn't fit the field
+	Io { path :String, reason :String },
+	Parse(String),
 }
 
-// Got sick of passing around the config everywhere. Let's try to make it a global variable. 
-use lazy_static::lazy_static;
-use std::sync::{Mutex}; // , MutexGuard};
-
-lazy_static!{
-    static ref CONFIG :Mutex<Config> = Mutex::new( Config { 
-		// these will all get wiped out anyway. It's so silly. 
-		// There must be a way to pass only the arguments that you need to apply. 
-		// Would probably have to be a HashMap, where keys are struct field names. 
-		// But Rust probably doesn't allow this. Can you access and set field values based on field name string, like js or python?
-		verbose: "low".to_string(),
-		population: 100,
-		lifespan: 50,
-		genome_size: 50,
-		use_chromo: true,
-		independent: false,
-		strength_mult: 4.0, // multiplier for gene strengths
-		// nodes: (5, 3, 5),
-		inputs: Vec::new(),
-		outputs: Vec::new(),
-		neurons: 5,
-	});
+impl ConfigError {
+	// name of the offending Config field (if it's about a field)
+	pub fn field(&self) -> Option<&str> {
+		match self {
+			ConfigError::Empty(field) | ConfigError::Zero(field) => Some(field),
+			ConfigError::TooManyNodes { field, .. } | ConfigError::Invalid { field, .. } => Some(field),
+			ConfigError::UnknownKey(key) => Some(key),
+			ConfigError::InvalidOverride(_) | ConfigError::Io { .. } | ConfigError::Parse(_) => None,
+		}
+	}
+}
+
+impl std::fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ConfigError::Empty(field) => write!(f, "config `{}` must not be empty", field),
+			ConfigError::Zero(field) => write!(f, "config `{}` must be greater than zero", field),
+			ConfigError::TooManyNodes { field, count } => 
+				write!(f, "config `{}` has {} nodes, but genes can only address {}", field, count, MAX_NODES),
+			ConfigError::Invalid { field, reason } => write!(f, "config `{}` is invalid: {}", field, reason),
+			ConfigError::UnknownKey(key) => write!(f, "unknown config key `{}`", key),
+			ConfigError::InvalidOverride(arg) => write!(f, "invalid config override `{}`, expected key=value", arg),
+			ConfigError::Io { path, reason } => write!(f, "could not read config {}: {}", path, reason),
+			ConfigError::Parse(reason) => write!(f, "could not parse config: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Default)]
+pub struct ConfigBuilder {
+	config :Config,
 }
 
-	// so here's kind of an issue...
-	// I want to be able to pass in only the config variables I care about
-	// Also want to "automagically" calculate other variables, like nodes
-	// Maybe a hashmap then? Where the values get copied over ... cleverly? Does rust even allow that? Probably not without unsafe. 
-	// Let's just spell it all out for now. 
+impl ConfigBuilder {
+	pub fn verbose(mut self, verbose :Verbosity) -> Self { self.config.verbose = verbose; self }
+	pub fn population(mut self, population :usize) -> Self { self.config.population = population; self }
+	pub fn lifespan(mut self, lifespan :usize) -> Self { self.config.lifespan = lifespan; self }
+	pub fn genome_size(mut self, genome_size :usize) -> Self { self.config.genome_size = genome_size; self }
+	pub fn use_chromo(mut self, use_chromo :bool) -> Self { self.config.use_chromo = use_chromo; self }
+	pub fn independent(mut self, independent :bool) -> Self { self.config.independent = independent; self }
+	pub fn strength_mult(mut self, strength_mult :f32) -> Self { self.config.strength_mult = strength_mult; self }
+	pub fn neurons(mut self, neurons :usize) -> Self { self.config.neurons = neurons; self }
+	pub fn hidden_activation(mut self, activation :Activation) -> Self { self.config.hidden_activation = activation; self }
+	pub fn output_activation(mut self, activation :Activation) -> Self { self.config.output_activation = activation; self }
+	pub fn evaluation(mut self, evaluation :Evaluation) -> Self { self.config.evaluation = evaluation; self }
+	pub fn think_ticks(mut self, think_ticks :usize) -> Self { self.config.think_ticks = think_ticks; self }
+	pub fn parents(mut self, parents :usize) -> Self { self.config.parents = parents; self }
+	pub fn crossover(mut self, crossover :Crossover) -> Self { self.config.crossover = crossover; self }
+	pub fn diploid(mut self, diploid :bool) -> Self { self.config.diploid = diploid; self }
+	pub fn dominance(mut self, dominance :Dominance) -> Self { self.config.dominance = dominance; self }
+	pub fn min_population(mut self, min_population :usize) -> Self { self.config.min_population = min_population; self }
+	pub fn reseed(mut self, reseed :Reseed) -> Self { self.config.reseed = reseed; self }
+	pub fn hall_of_fame(mut self, hall_of_fame :usize) -> Self { self.config.hall_of_fame = hall_of_fame; self }
+	pub fn seed(mut self, seed :u64) -> Self { self.config.seed = Some(seed); self }
+
+	pub fn inputs<I :IntoIterator<Item = S>, S :Into<String>>(mut self, inputs :I) -> Self {
+		self.config.inputs = inputs.into_iter().map(|s| s.into()).collect();
+		self
+	}
+
+	pub fn outputs<I :IntoIterator<Item = S>, S :Into<String>>(mut self, outputs :I) -> Self {
+		self.config.outputs = outputs.into_iter().map(|s| s.into()).collect();
+		self
+	}
+
+	// inputs named after a Sensors enum's variants [See: TypedCreature]
+	pub fn sensors<S :Sensors>(self) -> Self {
+		self.inputs( S::NAMES.iter().copied() )
+	}
+
+	// outputs named after an Actions enum's variants
+	pub fn actions<A :Actions>(self) -> Self {
+		self.outputs( A::NAMES.iter().copied() )
+	}
 
+	pub fn build(self) -> Result<Config, ConfigError> {
+		self.config.validate()?;
+		Ok( self.config )
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn builder() -> ConfigBuilder {
+		Config::builder().inputs(["X", "Y"]).outputs(["MOVX"])
+	}
+
+	#[test]
+	fn builds_with_defaults() {
+		let cfg = builder().build().unwrap();
+		assert_eq!( cfg.population, Config::default().population );
+		assert_eq!( cfg.node_count(), 2 + 5 + 1 );
+	}
+
+	#[test]
+	fn names_the_bad_field() {
+		assert_eq!( Config::builder().outputs(["MOVX"]).build().unwrap_err(), ConfigError::Empty("inputs") );
+		assert_eq!( builder().neurons(0).build().unwrap_err().field(), Some("neurons") );
+		assert_eq!( builder().lifespan(0).build().unwrap_err().field(), Some("lifespan") );
+		assert_eq!( builder().think_ticks(0).build().unwrap_err(), ConfigError::Zero("think_ticks") );
+		assert_eq!( builder().neurons(MAX_NODES + 1).build().unwrap_err(), ConfigError::TooManyNodes { field: "neurons", count: MAX_NODES + 1 } );
+		assert_eq!( builder().inputs(["X", "X"]).build().unwrap_err().field(), Some("inputs") );
+		assert_eq!( builder().population(10).min_population(11).build().unwrap_err().field(), Some("min_population") );
+		assert!( builder().population(10).min_population(10).build().is_ok() );
+		assert!( builder().neurons(MAX_NODES).build().is_ok() );
+	}
+
+	#[test]
+	fn loads_files_with_overrides() {
+		let dir = std::env::temp_dir().join( format!("gworld-config-{}", std::process::id()) );
+		std::fs::create_dir_all( &dir ).unwrap();
+		let toml_path = dir.join("cfg.toml");
+		std::fs::write( &toml_path, "inputs = [\"X\", \"Y\"]\noutputs = [\"MOVX\"]\nlifespan = 30\n" ).unwrap();
+		let json_path = dir.join("cfg.json");
+		std::fs::write( &json_path, r#"{ "inputs": ["X"], "outputs": ["MOVX"], "population": 7 }"# ).unwrap();
+
+		let cfg = Config::load( &toml_path, ["population=200", "strength_mult=2.5", "outputs=MOVX, MOVY", "verbose=high", "output_activation=leakyrelu"] ).unwrap();
+		assert_eq!( (cfg.lifespan, cfg.population, cfg.strength_mult), (30, 200, 2.5) );
+		assert_eq!( (cfg.hidden_activation, cfg.output_activation), (Activation::Tanh, Activation::LeakyRelu) );
+		assert_eq!( cfg.outputs, vec!["MOVX", "MOVY"] );
+		assert_eq!( cfg.verbose, Verbosity::High );
+		assert_eq!( Config::from_file( &json_path ).unwrap().population, 7 );
+
+		// whatever the field holds right now doesn't decide how the override is read
+		let mut cfg = Config::load( &toml_path, [r#"crossover={"npoint":3}"#] ).unwrap();
+		assert_eq!( cfg.crossover, Crossover::NPoint(3) );
+		cfg.apply_overrides( ["crossover=uniform"] ).unwrap();
+		assert_eq!( cfg.crossover, Crossover::Uniform );
+		cfg.apply_overrides( ["crossover=onepoint"] ).unwrap();
+		assert_eq!( cfg.crossover, Crossover::OnePoint );
+
+		assert_eq!( Config::load( &toml_path, ["popluation=200"] ).unwrap_err(), ConfigError::UnknownKey("popluation".into()) );
+		assert_eq!( Config::load( &toml_path, ["lifespan"] ).unwrap_err(), ConfigError::InvalidOverride("lifespan".into()) );
+		assert_eq!( Config::load( &toml_path, ["lifespan=0"] ).unwrap_err(), ConfigError::Zero("lifespan") );
+		std::fs::write( &toml_path, "inputs = [\"X\"]\noutputs = [\"Y\"]\nspeed = 3\n" ).unwrap();
+		assert_eq!( Config::from_file( &toml_path ).unwrap_err(), ConfigError::UnknownKey("speed".into()) );
+		std::fs::remove_dir_all( &dir ).ok();
+	}
+}
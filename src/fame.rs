@@ -0,0 +1,122 @@
+use super::genes::Genome;
+use super::organism::Organism;
+use super::persist::{self, PersistError, FameFile, FAME_VERSION};
+use super::{Config, Creature};
+use serde::{Serialize, Deserialize};
+use std::path::Path;
+
+// The "best performance list" [See: docs/deliverables.txt]
+// Organisms get archived here when they die, before World::birth reclaims the body, 
+// so the best genomes are never lost no matter when the run stops. Best first.
+#[derive(Clone)]
+pub struct HallOfFame {
+	capacity :usize,
+	config :Config, // what the champions were evolved with, their genomes don't make sense without it
+	champions :Vec<Champion>,
+}
+
+#[derive(Clone)]
+pub struct Champion {
+	pub genome :Genome,
+	pub max_fitness :f32,
+	pub age :usize,
+	pub offspring :usize,
+	pub born :usize, // world clock at birth
+}
+
+// what goes in the file, genome as hex
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ChampionRecord {
+	pub(crate) genome :String,
+	pub(crate) max_fitness :f32,
+	pub(crate) age :usize,
+	pub(crate) offspring :usize,
+	pub(crate) born :usize,
+}
+
+impl HallOfFame {
+	// room for Config.hall_of_fame champions
+	pub fn new(cfg :&Config) -> Self {
+		Self { capacity: cfg.hall_of_fame, config: cfg.clone(), champions: Vec::new() }
+	}
+
+	pub fn config(&self) -> &Config {
+		&self.config
+	}
+
+	pub fn champions(&self) -> &[Champion] {
+		&self.champions
+	}
+
+	pub fn best(&self) -> Option<&Champion> {
+		self.champions.first()
+	}
+
+	pub fn len(&self) -> usize {
+		self.champions.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.champions.is_empty()
+	}
+
+	// json, or bincode for .bin/.bincode (genomes as hex, see Genome::from_hex). The config goes along with them.
+	pub fn save<P :AsRef<Path>>(&self, path :P) -> Result<(), PersistError> {
+		let file = FameFile {
+			version: FAME_VERSION,
+			config: self.config.clone(),
+			champions: self.records(),
+		};
+		persist::write_file( path.as_ref(), &file )
+	}
+
+	// Whatever save wrote, ie: Network::from_genome( &fame.best().unwrap().genome, fame.config() )
+	pub fn load<P :AsRef<Path>>(path :P) -> Result<Self, PersistError> {
+		let file :FameFile = persist::read_file( path.as_ref() )?;
+		if file.version != FAME_VERSION {
+			return Err( PersistError::Version( file.version.to_string() ) )
+		}
+		Self::from_records( file.champions, &file.config )
+	}
+
+	// Only clones the genome if it makes the cut
+	pub(crate) fn consider<T :Creature<CCT = T>>(&mut self, org :&Organism<T>) {
+		if let Some(at) = self.rank( org.max_fitness ) {
+			self.champions.insert( at, Champion {
+				genome: org.genome.clone(),
+				max_fitness: org.max_fitness,
+				age: org.age,
+				offspring: org.offspring,
+				born: org.born,
+			});
+			self.champions.truncate( self.capacity );
+		}
+	}
+
+	// where it would go, if it's good enough (ties go behind the older champions)
+	fn rank(&self, max_fitness :f32) -> Option<usize> {
+		let at = self.champions.iter().position(|c| max_fitness > c.max_fitness).unwrap_or( self.champions.len() );
+		if at < self.capacity { Some(at) } else { None }
+	}
+
+	pub(crate) fn records(&self) -> Vec<ChampionRecord> {
+		self.champions.iter().map(|c| ChampionRecord {
+			genome: c.genome.to_hex(),
+			max_fitness: c.max_fitness,
+			age: c.age,
+			offspring: c.offspring,
+			born: c.born,
+		}).collect()
+	}
+
+	pub(crate) fn from_records(records :Vec<ChampionRecord>, cfg :&Config) -> Result<Self, PersistError> {
+		let mut champions = Vec::new();
+		for r in records {
+			champions.push( Champion { 
+				genome: Genome::from_hex( &r.genome, cfg )?, 
+				max_fitness: r.max_fitness, age: r.age, offspring: r.offspring, born: r.born,
+			});
+		}
+		Ok( Self { capacity: cfg.hall_of_fame, config: cfg.clone(), champions } )
+	}
+}
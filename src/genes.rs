@@ -1,6 +1,9 @@
 use super::node::{Node, NodeType};
 use super::config::Config;
+use super::world::WorldRng;
+use super::persist::PersistError;
 use rand::Rng; 
+use serde::{Serialize, Deserialize};
 
 #[derive(Copy, Clone)]
 pub(crate) struct Gene {
@@ -19,26 +22,24 @@ impl std::fmt::Display for Gene {
 }
 
 impl Gene {
-	pub(crate) fn new() -> Self {
-		let mut rng = rand::thread_rng();	
+	pub(crate) fn new(cfg :&Config, rng :&mut WorldRng) -> Self {
 		let dna = rng.gen();
-		Gene::with_dna( dna )
+		Gene::with_dna( dna, cfg )
 	}
 
-	pub(crate) fn with_dna(dna :u32) -> Self {
+	pub(crate) fn with_dna(dna :u32, cfg :&Config) -> Self {
 		Gene {
-			dna: dna,
+			dna,
 			active: false, // inactive on creation 
-			source: Gene::get_source(dna),
-			sink: Gene::get_sink(dna),
-			strength: Gene::get_strength(dna),
+			source: Gene::get_source(dna, cfg),
+			sink: Gene::get_sink(dna, cfg),
+			strength: Gene::get_strength(dna, cfg),
 		}
 	}	
 	
 	// slight strength adjustment
-	pub(crate) fn mutate(&self) -> Self {
+	pub(crate) fn mutate(&self, cfg :&Config, rng :&mut WorldRng) -> Self {
 		// print!("Before: {:X}, ", self.dna);
-		let mut rng = rand::thread_rng();
 		let r = rng.gen_range(0.8..1.2); // might want a bell curve here
 		let s = (self.dna & 0xffff) as i16 as f32;
 		let t = f32::max( i16::MIN as f32, 
@@ -48,59 +49,63 @@ impl Gene {
 		let dna = (self.dna & 0xffff0000) | (0x0000ffff & t as i32 as u32);
 		// println!("After: {:X}", dna);
 
-		Gene::with_dna(dna) 
+		Gene::with_dna(dna, cfg) 
 	}
 
 	// swaps two bits
-	pub(crate) fn _mutate(&self) -> Self {
-		let mut rng = rand::thread_rng();
+	pub(crate) fn _mutate(&self, cfg :&Config, rng :&mut WorldRng) -> Self {
 		let b1 = rng.gen_range(0..32);
 		let b2 = rng.gen_range(0..32);
 
 		// let's swap two bits 
 		let mut dna = self.dna;
-		dna = dna ^ (1 << b1);
-		dna = dna ^ (1 << b2);
+		dna ^= 1 << b1;
+		dna ^= 1 << b2;
 		
-		Gene::with_dna(dna) // just return new one for now...
+		Gene::with_dna(dna, cfg) // just return new one for now...
 	}
 
-	pub(crate) fn get_strength(dna :u32) -> f32 {
+	// the lowest strength bit doubles as the dominance flag (when Config.dominance is Bit). Barely moves the strength.
+	pub(crate) fn is_dominant(&self) -> bool {
+		self.dna & 1 == 1
+	}
+
+	pub(crate) fn get_strength(dna :u32, cfg :&Config) -> f32 {
 		let s = (dna & 0xffff) as i16;
 		let div = (0xffffu16 >> 1) as i16;
-		(s as f32) / (div as f32) * Config::get().strength_mult
+		(s as f32) / (div as f32) * cfg.strength_mult
 	}
 
-	pub(crate) fn get_source(dna :u32) -> Node {
+	pub(crate) fn get_source(dna :u32, cfg :&Config) -> Node {
 		let byte = ((dna >> 24) & 0xff) as u8; // source in the first byte
 		let node_type = NodeType::INPUT;
-		Gene::get_node( byte, node_type )
+		Gene::get_node( byte, node_type, cfg )
 	}
 
-	pub(crate) fn get_sink(dna :u32) -> Node {
+	pub(crate) fn get_sink(dna :u32, cfg :&Config) -> Node {
 		let byte = ((dna >> 16) & 0xff) as u8; // sink in the second byte
 		let node_type = NodeType::OUTPUT;
-		Gene::get_node( byte, node_type )
+		Gene::get_node( byte, node_type, cfg )
 	}
 
-	pub(crate) fn get_node(byte :u8, mut node_type :NodeType) -> Node {
+	pub(crate) fn get_node(byte :u8, mut node_type :NodeType, cfg :&Config) -> Node {
 		if 0x80 == (byte & 0x80) { // switch node type if first bit set
 			node_type = NodeType::HIDDEN;
 		}
 		let index = (byte & 0b01111111) as usize; // mask out first bit
 		let idx = match node_type {
-			NodeType::INPUT => index % Config::get().inputs.len(),
-			NodeType::HIDDEN => Config::get().inputs.len() + (index % Config::get().neurons),
-			NodeType::OUTPUT => Config::get().inputs.len() + Config::get().neurons + (index % Config::get().outputs.len()),
+			NodeType::INPUT => index % cfg.inputs.len(),
+			NodeType::HIDDEN => cfg.inputs.len() + (index % cfg.neurons),
+			NodeType::OUTPUT => cfg.inputs.len() + cfg.neurons + (index % cfg.outputs.len()),
 		};
-		Node { idx:idx, node_type:node_type }
+		Node { idx, node_type }
 	}
 }
 
 impl Gene { 
-	fn set_active_genes(genes :&mut Vec<Gene>) { // not sure where to put this function. Auxilary really. 
-		let mut sourcing = vec![false; Config::get().node_count()]; // create dense vectors 
-		let mut sinking = vec![false; Config::get().node_count()]; // to help us keep track of nodes that are potentially sourcing or sinking signals.
+	fn set_active_genes(genes :&mut [Gene], cfg :&Config) { // not sure where to put this function. Auxilary really. 
+		let mut sourcing = vec![false; cfg.node_count()]; // create dense vectors 
+		let mut sinking = vec![false; cfg.node_count()]; // to help us keep track of nodes that are potentially sourcing or sinking signals.
 		let mut hidden_edges = Vec::new();
 
 		for g in genes.iter() {
@@ -169,66 +174,107 @@ impl Gene {
 					g.active = true;
 				}
 			} else {
-				println!("Something bizarre happening: {}", g);
+				tracing::warn!(gene = %g, "Something bizarre happening, gene doesn't run from a source to a sink");
 			}
 		}
 	}
 }
 
 
+#[derive(Clone)]
 pub(crate) struct Chromo {
 	pub(crate) genes :Vec<Gene>, // contains info for working 
 }
 
 impl Chromo {
-	fn init() -> Self {
-		let mut genes = Vec::new();
-		genes.push( Gene::new() ); // we'll always have at least one gene
+	fn init(cfg :&Config, rng :&mut WorldRng) -> Self {
+		let genes = vec![ Gene::new(cfg, rng) ]; // we'll always have at least one gene
 		Self {
-			genes: genes,
+			genes,
 		}
 	}
 
-	fn new() -> Self {
-		let mut chromo = Chromo::init();
-		while !chromo.is_complete() {
-			chromo.genes.push( Gene::new() );
+	fn new(cfg :&Config, rng :&mut WorldRng) -> Self {
+		let mut chromo = Chromo::init(cfg, rng);
+		while !chromo.is_complete(cfg) {
+			chromo.genes.push( Gene::new(cfg, rng) );
 		}
 		return chromo
 	}
 
 	// return a mutated copy of ourself
-	fn mutate(&self) -> Self { 
+	fn mutate(&self, cfg :&Config, rng :&mut WorldRng) -> Self { 
 		// Let's have one mutation per chromosome (for now)
-        let mut rng = rand::thread_rng();
 		let chance = 1. / (self.genes.len() as f32); // chance for any gene to mutate
 		
 		let mut genes = Vec::new();
 		for gene in self.genes.iter() {
 			if chance >= rng.gen_range(0.0..1.0) {
-				genes.push( gene.mutate() );
+				genes.push( gene.mutate(cfg, rng) );
 			} else {
-				genes.push( gene.clone() );
+				genes.push( *gene );
 			}
 		}
 		let mut chromo = Chromo { genes };
-		while !chromo.is_complete() {
-			chromo.genes.push( Gene::new() );
+		while !chromo.is_complete(cfg) {
+			chromo.genes.push( Gene::new(cfg, rng) );
 		}
 		return chromo
 	}
 
-	fn is_complete(&mut self) -> bool {
-		if Config::get().use_chromo {
-			self.set_active();
+	// Which genes of a homologous pair get expressed. Genes line up by position, 
+	// and the leftovers of the longer chromosome come along for free.
+	fn express(a :&Chromo, b :&Chromo, cfg :&Config) -> Self {
+		let mut genes = Vec::new();
+		if cfg.dominance == Dominance::Codominant {
+			genes.extend( a.genes.iter().chain( b.genes.iter() ) );
+		} else {
+			for i in 0..usize::max( a.genes.len(), b.genes.len() ) {
+				let gene = match (a.genes.get(i), b.genes.get(i)) {
+					(Some(ga), Some(gb)) => {
+						let stronger = if f32::abs(gb.strength) > f32::abs(ga.strength) { gb } else { ga };
+						match cfg.dominance {
+							Dominance::Bit if ga.is_dominant() != gb.is_dominant() => if ga.is_dominant() { ga } else { gb },
+							_ => stronger, // Stronger, or the dominance bit can't decide
+						}
+					},
+					(Some(g), None) | (None, Some(g)) => g,
+					(None, None) => unreachable!(),
+				};
+				genes.push( *gene );
+			}
+		}
+		let mut chromo = Chromo { genes };
+		chromo.set_active(cfg);
+		chromo
+	}
+
+	fn from_hex(hex :&str, cfg :&Config) -> Result<Self, PersistError> {
+		if hex.is_empty() || !hex.len().is_multiple_of(8) || !hex.is_ascii() {
+			return Err( PersistError::Parse( format!("bad chromosome \"{}\"", hex) ) )
+		}
+		let genes = (0..hex.len()).step_by(8)
+			.map(|i| u32::from_str_radix( &hex[i..i+8], 16 ).map(|dna| Gene::with_dna( dna, cfg )))
+			.collect::<Result<Vec<Gene>, _>>()
+			.map_err(|e| PersistError::Parse( format!("bad chromosome \"{}\": {}", hex, e) ))?;
+		let mut chromo = Chromo { genes };
+		if cfg.use_chromo { // same as is_complete would have left it
+			chromo.set_active(cfg);
+		}
+		Ok( chromo )
+	}
+
+	fn is_complete(&mut self, cfg :&Config) -> bool {
+		if cfg.use_chromo {
+			self.set_active(cfg);
 			self.is_active() // make sure chromosome is a functional network.
 		} else {
-			self.genes.len() >= 1
+			!self.genes.is_empty()
 		}
 	}
 
-	fn set_active(&mut self) { // -> Bool (we could get rid of these two functions)
-		Gene::set_active_genes( &mut self.genes );
+	fn set_active(&mut self, cfg :&Config) { // -> Bool (we could get rid of these two functions)
+		Gene::set_active_genes( &mut self.genes, cfg );
 	}
 
 	fn is_active(&self) -> bool {
@@ -241,42 +287,108 @@ impl Chromo {
 	}
 }
 
-pub(crate) struct Genome {
-	pub(crate) chromos :Vec<Chromo>,
+// The "hereditary" half of an organism. Save it with to_hex, bring it back with from_hex. 
+#[derive(Clone)]
+pub struct Genome {
+	pub(crate) chromos :Vec<Chromo>, // the expressed set, this is what the brain gets built from
+	pub(crate) homologs :Option<[Vec<Chromo>; 2]>, // diploid only: both sets, chromos gets expressed from these
 }
 
 impl Genome {
-	pub fn new() -> Self {
+	pub fn new(cfg :&Config, rng :&mut WorldRng) -> Self {
+		if cfg.diploid {
+			let sets = [ Genome::random_set(cfg, rng), Genome::random_set(cfg, rng) ];
+			return Genome::with_homologs( sets, cfg )
+		}
+		Genome::with_chromos( Genome::random_set(cfg, rng), cfg )
+	}
+
+	fn random_set(cfg :&Config, rng :&mut WorldRng) -> Vec<Chromo> {
 		let mut chromos = Vec::new();
-		for _i in 0..Config::get().genome_size {
-			chromos.push( Chromo::new() );
+		for _i in 0..cfg.genome_size {
+			chromos.push( Chromo::new(cfg, rng) );
 		}
-		Genome::with_chromos( chromos )
+		chromos
 	}
 		
-	pub fn with_chromos( chromos :Vec<Chromo> ) -> Self {
+	pub(crate) fn with_chromos( chromos :Vec<Chromo>, cfg :&Config ) -> Self {
 		let mut genome = Self {
-			chromos: chromos,
+			chromos,
+			homologs: None,
 		};
 
-		genome.set_active_genes(); // Find any more genes that may be actived from combined chromosome networks
+		genome.set_active_genes(cfg); // Find any more genes that may be actived from combined chromosome networks
 		// do we really need to be doing this here, or when we build the brains out?
 		// Just do it. Building a genome always finds/sets the active genes. Sounds like a good rule to me.
 		
 		return genome
 	}
 
+	// Two homologous sets, one chromosome from each pair gets expressed by the Config.dominance rule
+	pub(crate) fn with_homologs( sets :[Vec<Chromo>; 2], cfg :&Config ) -> Self {
+		let chromos = sets[0].iter().zip( sets[1].iter() )
+			.map(|(a, b)| Chromo::express( a, b, cfg ))
+			.collect();
+		let mut genome = Genome::with_chromos( chromos, cfg );
+		genome.homologs = Some( sets );
+		genome
+	}
+
+	// Half of the homologs (one per pair, picked at random) each mutated. Haploid genomes just pass their set.
+	fn gamete(&self, cfg :&Config, rng :&mut WorldRng) -> Vec<Chromo> {
+		match &self.homologs {
+			Some( sets ) => (0..sets[0].len()).map(|i| sets[ rng.gen_range(0..2) ][i].mutate(cfg, rng)).collect(),
+			None => self.chromos.iter().map(|c| c.mutate(cfg, rng)).collect(),
+		}
+	}
+
 	// Asexual reproduction method, creates a mutated clone
-	pub fn bud(&self) -> Self {
+	pub(crate) fn bud(&self, cfg :&Config, rng :&mut WorldRng) -> Self {
+		if let Some( [a, b] ) = &self.homologs { // keep both sets
+			let mutate = |set :&Vec<Chromo>, rng :&mut WorldRng| set.iter().map(|c| c.mutate(cfg, rng)).collect();
+			let sets = [ mutate(a, rng), mutate(b, rng) ];
+			return Genome::with_homologs( sets, cfg )
+		}
+
 		// First let's build the chromosomes
 		let mut chromos = Vec::new();
 		for chro in self.chromos.iter() {
-			chromos.push( chro.mutate() );
+			chromos.push( chro.mutate(cfg, rng) );
+		}
+		Genome::with_chromos( chromos, cfg )
+	}
+
+	// Sexual reproduction method, each chromosome comes whole from one of the parents, then gets the usual mutation
+	// When diploid, it's meiosis instead: a gamete from each of the first two parents.
+	pub(crate) fn crossover(parents :&[&Genome], cfg :&Config, rng :&mut WorldRng) -> Self {
+		if cfg.diploid {
+			let sets = [ parents[0].gamete(cfg, rng), parents[1 % parents.len()].gamete(cfg, rng) ];
+			return Genome::with_homologs( sets, cfg )
 		}
-		Genome::with_chromos( chromos )
+
+		let len = parents.iter().map(|p| p.chromos.len()).max().unwrap_or(0);
+		let cuts = match cfg.crossover {
+			Crossover::Uniform => Vec::new(),
+			Crossover::OnePoint => cut_points( 1, len, rng ),
+			Crossover::NPoint(n) => cut_points( n, len, rng ),
+		};
+
+		let mut chromos = Vec::new();
+		for i in 0..len {
+			let pick = match cfg.crossover {
+				Crossover::Uniform => rng.gen_range(0..parents.len()),
+				_ => cuts.iter().filter(|cut| **cut <= i).count() % parents.len(), // next segment, next parent
+			};
+			// parents with fewer chromosomes can't donate past their end, fall back to one who can
+			let parent = if i < parents[pick].chromos.len() { parents[pick] } else {
+				parents.iter().find(|p| i < p.chromos.len()).unwrap()
+			};
+			chromos.push( parent.chromos[i].mutate(cfg, rng) );
+		}
+		Genome::with_chromos( chromos, cfg )
 	}
 	
-	fn set_active_genes(&mut self) {
+	fn set_active_genes(&mut self, cfg :&Config) {
 		// We need to pull all the genes together. 
 		let mut genes = Vec::new();
 		for chro in self.chromos.iter_mut() {
@@ -284,9 +396,56 @@ impl Genome {
 				genes.push( *g )
 			}
 		}
-		Gene::set_active_genes( &mut genes );
+		Gene::set_active_genes( &mut genes, cfg );
 	}
 	
+	// expressed genes that didn't
+	pub(crate) fn inactive_genes(&self) -> impl Iterator<Item = Gene> + '_ {
+		self.chromos.iter().flat_map(|c| c.genes.iter()).filter(|g| !g.active).copied()
+	}
+
+	// expressed genes that made it into a working network
+	pub(crate) fn active_genes(&self) -> usize {
+		self.chromos.iter().map(|c| c.genes.iter().filter(|g| g.active).count()).sum()
+	}
+
+	// Format (v1): "gw1:" then the chromosomes as hex dna, 8 digits per gene, separated by commas. 
+	// Diploid genomes have both homolog sets, separated by a "|". ie:
+	//   gw1:1f03a2c45e0f0012,8a01b2c3          (haploid, 2 chromosomes)
+	//   gw1:1f03a2c4,8a01b2c3|0e11a2c4,8a01ffc3 (diploid)
+	// Only the dna is stored, what it means (node names, strength_mult) comes from the Config it's loaded with.
+	pub fn to_hex(&self) -> String {
+		let set = |chromos :&[Chromo]| chromos.iter()
+			.map(|c| c.genes.iter().map(|g| format!("{:08x}", g.dna)).collect::<String>())
+			.collect::<Vec<String>>().join(",");
+		match &self.homologs {
+			Some( [a, b] ) => format!("{}{}|{}", HEX_VERSION, set(a), set(b)),
+			None => format!("{}{}", HEX_VERSION, set(&self.chromos)),
+		}
+	}
+
+	pub fn from_hex(hex :&str, cfg :&Config) -> Result<Genome, PersistError> {
+		let body = hex.trim().strip_prefix( HEX_VERSION ).ok_or_else(|| match hex.split_once(':') {
+			Some((version, _)) => PersistError::Version( version.to_string() ),
+			None => PersistError::Parse( "genome is missing its version prefix".to_string() ),
+		})?;
+		let set = |s :&str| -> Result<Vec<Chromo>, PersistError> {
+			s.split(',').map(|c| Chromo::from_hex( c, cfg )).collect()
+		};
+		let sets :Vec<&str> = body.split('|').collect();
+		match (sets.as_slice(), cfg.diploid) {
+			([one], false) => Ok( Genome::with_chromos( set(one)?, cfg ) ),
+			([a, b], true) => {
+				let sets = [ set(a)?, set(b)? ];
+				if sets[0].len() != sets[1].len() {
+					return Err( PersistError::Parse( "homolog sets have different lengths".to_string() ) )
+				}
+				Ok( Genome::with_homologs( sets, cfg ) )
+			},
+			(_, diploid) => Err( PersistError::Mismatch( format!("expected a {} genome", if diploid { "diploid" } else { "haploid" }) ) ),
+		}
+	}
+
 	#[allow(dead_code)]
     fn print_genes(&self) {
 		print!("Genome: ");
@@ -299,3 +458,131 @@ impl Genome {
 		println!("END");
     }
 }
+
+const HEX_VERSION :&str = "gw1:";
+
+// n distinct positions (1..len) where we switch to the next parent, sorted
+fn cut_points(n :usize, len :usize, rng :&mut WorldRng) -> Vec<usize> {
+	let mut cuts :Vec<usize> = (1..len).collect();
+	for i in 0..usize::min( n, cuts.len() ) { // partial shuffle, keep the first n
+		let j = rng.gen_range(i..cuts.len());
+		cuts.swap( i, j );
+	}
+	cuts.truncate( n );
+	cuts.sort_unstable();
+	cuts
+}
+
+// How chromosomes get picked from the parents, when there's more than one [See: Config.parents]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Crossover {
+	Uniform, // each chromosome from a random parent
+	OnePoint, // first stretch from one parent, the rest from the next
+	NPoint(usize), // n cuts, switching to the next parent at each one
+}
+
+// Which gene of a homologous pair gets expressed [See: Config.diploid]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dominance {
+	Stronger, // the one with the larger |strength|
+	Codominant, // both of them
+	Bit, // the one with the dominance bit set (lowest bit of the dna), stronger breaks ties
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+
+	// which parent did each chromosome come from? (mutation only touches the strength bits)
+	fn origins(child :&Genome, parents :&[&Genome]) -> Vec<usize> {
+		child.chromos.iter().enumerate().map(|(i, c)| {
+			parents.iter().position(|p| p.chromos[i].genes[0].dna >> 16 == c.genes[0].dna >> 16).unwrap()
+		}).collect()
+	}
+
+	#[test]
+	fn crossover_segments() {
+		let cfg = Config { genome_size: 8, ..crate::world::tests::config() };
+		let mut rng = WorldRng::seed_from_u64( 3 );
+		let (a, b) = (Genome::new(&cfg, &mut rng), Genome::new(&cfg, &mut rng));
+		let parents = [&a, &b];
+
+		let one = Config { crossover: Crossover::OnePoint, ..cfg.clone() };
+		for _ in 0..10 {
+			let child = Genome::crossover( &parents, &one, &mut rng );
+			let from = origins( &child, &parents );
+			assert_eq!( from[0], 0 );
+			assert_eq!( from.windows(2).filter(|w| w[0] != w[1]).count(), 1 ); // exactly one switch
+		}
+
+		let three = Config { crossover: Crossover::NPoint(3), ..cfg.clone() };
+		let child = Genome::crossover( &parents, &three, &mut rng );
+		assert_eq!( origins( &child, &parents ).windows(2).filter(|w| w[0] != w[1]).count(), 3 );
+
+		let child = Genome::crossover( &parents, &cfg, &mut rng ); // uniform
+		assert_eq!( child.chromos.len(), 8 );
+	}
+
+	fn chromo(strengths :&[i16], cfg :&Config) -> Chromo {
+		// input 0 -> output 0, only the strength differs
+		Chromo { genes: strengths.iter().map(|s| Gene::with_dna( *s as u16 as u32, cfg )).collect() }
+	}
+
+	#[test]
+	fn diploid_expression() {
+		let cfg = crate::world::tests::config();
+		let (a, b) = (chromo( &[100, -3001, 7], &cfg ), chromo( &[-3000, 200], &cfg ));
+		let dna = |c :&Chromo| c.genes.iter().map(|g| g.dna as u16 as i16).collect::<Vec<i16>>();
+
+		let stronger = Config { dominance: Dominance::Stronger, ..cfg.clone() };
+		assert_eq!( dna( &Chromo::express( &a, &b, &stronger ) ), vec![-3000, -3001, 7] );
+		let both = Config { dominance: Dominance::Codominant, ..cfg.clone() };
+		assert_eq!( dna( &Chromo::express( &a, &b, &both ) ), vec![100, -3001, 7, -3000, 200] );
+		let bit = Config { dominance: Dominance::Bit, ..cfg.clone() };
+		assert_eq!( dna( &Chromo::express( &a, &b, &bit ) ), vec![-3000, -3001, 7] ); // 100 and -3000 even: stronger decides
+		let (a, b) = (chromo( &[101, -3001], &cfg ), chromo( &[-3000, 201], &cfg ));
+		assert_eq!( dna( &Chromo::express( &a, &b, &bit ) ), vec![101, -3001] );
+		assert!( Chromo::express( &a, &b, &bit ).is_active() );
+	}
+
+	#[test]
+	fn diploid_genomes() {
+		let cfg = Config { diploid: true, ..crate::world::tests::config() };
+		let mut rng = WorldRng::seed_from_u64( 5 );
+		let (a, b) = (Genome::new(&cfg, &mut rng), Genome::new(&cfg, &mut rng));
+		for child in [ a.bud(&cfg, &mut rng), Genome::crossover( &[&a, &b], &cfg, &mut rng ) ] {
+			let sets = child.homologs.as_ref().unwrap();
+			assert_eq!( (sets[0].len(), sets[1].len(), child.chromos.len()), (3, 3, 3) );
+		}
+		// meiosis: the child's first set comes from a, the second from b
+		let child = Genome::crossover( &[&a, &b], &cfg, &mut rng );
+		let from = |set :&Vec<Chromo>, parent :&Genome| set.iter().enumerate().all(|(i, c)| {
+			parent.homologs.as_ref().unwrap().iter().any(|h| h[i].genes[0].dna >> 16 == c.genes[0].dna >> 16)
+		});
+		let sets = child.homologs.as_ref().unwrap();
+		assert!( from( &sets[0], &a ) && from( &sets[1], &b ) );
+	}
+
+	#[test]
+	fn hex_round_trip() {
+		for diploid in [false, true] {
+			let cfg = Config { diploid, ..crate::world::tests::config() };
+			let mut rng = WorldRng::seed_from_u64( 9 );
+			let genome = Genome::new( &cfg, &mut rng );
+			let hex = genome.to_hex();
+			let back = Genome::from_hex( &hex, &cfg ).unwrap();
+			assert_eq!( back.to_hex(), hex );
+			let flags = |g :&Genome| g.chromos.iter().flat_map(|c| c.genes.iter().map(|g| (g.dna, g.active))).collect::<Vec<_>>();
+			assert_eq!( flags(&back), flags(&genome) );
+			let other = Config { diploid: !diploid, ..cfg.clone() };
+			assert!( matches!( Genome::from_hex( &hex, &other ), Err( PersistError::Mismatch(_) ) ) );
+		}
+		let cfg = crate::world::tests::config();
+		assert_eq!( Genome::from_hex( "gw9:00000000", &cfg ).err(), Some( PersistError::Version("gw9".into()) ) );
+		assert!( matches!( Genome::from_hex( "gw1:0000000", &cfg ), Err( PersistError::Parse(_) ) ) );
+		assert!( matches!( Genome::from_hex( "gw1:0000000g", &cfg ), Err( PersistError::Parse(_) ) ) );
+	}
+}
@@ -1,5 +1,7 @@
 // A library to enable genetically-inspired breeding algorithms. 
 
+extern crate self as gworld; // so the derive macros work in here too
+
 // now all other modules can access following through crate/super
 pub mod math;
 mod world;
@@ -8,9 +10,29 @@ mod brains;
 mod organism;
 mod genes;
 mod node;
+mod selection;
+mod persist;
+mod fame;
+mod stats;
+mod run;
+mod typed;
+mod network;
+mod codegen;
 
-pub use world::{World, Creature, Environs};
-pub use config::Config; 
+pub use world::{World, WorldRng, Creature, IndependentCreature, NodeMask, Environs, Reseed, Extinction};
+pub use genes::{Genome, Crossover, Dominance};
+pub use network::Network;
+pub use codegen::CodegenError;
+pub use persist::PersistError;
+pub use fame::{HallOfFame, Champion};
+pub use stats::{PopulationStats, StatsRecorder, StatsFormat};
+pub use run::{StopCriteria, StopReason, RunSummary};
+pub use selection::{SelectionStrategy, Roulette, Tournament, Rank, RankPressure, Truncation, StochasticUniversal};
+pub use math::Activation;
+pub use config::{Config, ConfigBuilder, ConfigError, Verbosity, Evaluation, MAX_NODES};
+pub use typed::{Sensors, Actions, TypedCreature};
+#[cfg(feature = "derive")]
+pub use gworld_derive::{Sensors, Actions};
 
 #[cfg(test)]
 mod tests { // Yeah I need to do this. 
@@ -1,4 +1,5 @@
 pub use std::f32::consts::PI;
+use serde::{Serialize, Deserialize};
 
 // I feel like these are way too "bunchy". 
 // Like, I'll get stuck at far ends for d. 
@@ -27,3 +28,47 @@ pub fn tanh(x:f32) ->f32 { // yields Range(-1,1)
 pub fn rectified(x:f32) ->f32 {
     f32::max(0.0, x)
 }
+
+// leaky rectified linear function, a little slope below zero so it doesn't go dead
+pub fn leaky_rectified(x:f32) ->f32 {
+    if x > 0.0 { x } else { 0.01 * x }
+}
+
+// heaviside step, all or nothing
+pub fn step(x:f32) ->f32 {
+    if x > 0.0 { 1.0 } else { 0.0 }
+}
+
+// bell curve, peaks at 1 when x is 0
+pub fn gaussian(x:f32) ->f32 {
+    f32::exp(-x * x)
+}
+
+// So we can pick the squashing per layer. [See: Config.hidden_activation, Config.output_activation]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Activation {
+    Identity, // raw
+    Tanh,
+    Sigmoid,
+    Relu,
+    LeakyRelu,
+    Step,
+    Gaussian,
+    Sine,
+}
+
+impl Activation {
+    pub fn apply(self, x:f32) ->f32 {
+        match self {
+            Activation::Identity => x,
+            Activation::Tanh => tanh(x),
+            Activation::Sigmoid => sigmoid(x),
+            Activation::Relu => rectified(x),
+            Activation::LeakyRelu => leaky_rectified(x),
+            Activation::Step => step(x),
+            Activation::Gaussian => gaussian(x),
+            Activation::Sine => f32::sin(x),
+        }
+    }
+}
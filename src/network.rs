@@ -0,0 +1,145 @@
+use super::brains::Brain;
+use super::codegen::{self, CodegenError};
+use super::genes::Genome;
+use super::persist::{self, PersistError, PopulationFile, POPULATION_VERSION};
+use super::fame::HallOfFame;
+use super::Config;
+use std::path::Path;
+
+// An evolved brain on its own, for running a champion in production: no World, Environs or Creature needed.
+// Same evaluation as an organism's step (activations, evaluation mode and think_ticks all come from the config), 
+// so give it the config the genome was trained with.
+// ie: let mut net = Network::from_hex( &hex, &config )?; let outputs = net.step( &[x, y] );
+// or straight from what a run saved: Network::from_champion_file( "best.json" )?
+pub struct Network {
+	brain :Brain,
+	think_ticks :usize,
+	inputs :Vec<String>, // the config's names, for to_rust
+	outputs :Vec<String>,
+}
+
+impl Network {
+	pub fn from_genome(genome :&Genome, cfg :&Config) -> Self {
+		Self { 
+			brain: Brain::new( genome, cfg ), 
+			think_ticks: cfg.think_ticks, 
+			inputs: cfg.inputs.clone(), 
+			outputs: cfg.outputs.clone(),
+		}
+	}
+
+	// the Genome::to_hex format, as saved by save_population, checkpoints and the hall of fame
+	pub fn from_hex(hex :&str, cfg :&Config) -> Result<Self, PersistError> {
+		Ok( Self::from_genome( &Genome::from_hex( hex, cfg )?, cfg ) )
+	}
+
+	// The best champion in a HallOfFame::save file, with the config it was saved with
+	pub fn from_champion_file<P :AsRef<Path>>(path :P) -> Result<Self, PersistError> {
+		let fame = HallOfFame::load( path )?;
+		let best = fame.best().ok_or_else(|| PersistError::Parse( "no champions in the file".to_string() ))?;
+		Ok( Self::from_genome( &best.genome, fame.config() ) )
+	}
+
+	// Everybody in a World::save_population file, in the order they were saved
+	pub fn from_population_file<P :AsRef<Path>>(path :P) -> Result<Vec<Self>, PersistError> {
+		let file :PopulationFile = persist::read_file( path.as_ref() )?;
+		if file.version != POPULATION_VERSION {
+			return Err( PersistError::Version( file.version.to_string() ) )
+		}
+		file.genomes.iter().map(|hex| Self::from_hex( hex, &file.config )).collect()
+	}
+
+	// One step. inputs in Config.inputs order, outputs come back in Config.outputs order (0 for any the brain doesn't use).
+	// Like an organism, the brain remembers its state between steps. [See: reset]
+	pub fn step(&mut self, inputs :&[f32]) -> &[f32] {
+		assert_eq!( inputs.len(), self.brain.input_mask.len(), "expected one value per Config.inputs" );
+		self.brain.node_state[ ..inputs.len() ].copy_from_slice( inputs );
+		for _tick in 0..self.think_ticks {
+			self.brain.process();
+		}
+		&self.brain.output_values
+	}
+
+	// back to a newborn's blank state
+	pub fn reset(&mut self) {
+		self.brain.reset();
+	}
+
+	// the last step's outputs
+	pub fn outputs(&self) -> &[f32] {
+		&self.brain.output_values
+	}
+
+	// which of Config.inputs the brain actually listens to, the rest can be anything
+	pub fn input_mask(&self) -> &[bool] {
+		&self.brain.input_mask
+	}
+
+	// Rust source for this brain: `pub mod <module>` with a `Brain` (`new`, `reset`, `step`) and the weights as constants.
+	// Doesn't need gworld (or anything else) to build, and steps exactly like this Network.
+	// module has to be a plain Rust identifier (it's pasted into `pub mod`).
+	// ie: std::fs::write( "src/champion.rs", net.to_rust("champion")? )?; ... let mut brain = champion::Brain::new();
+	pub fn to_rust(&self, module :&str) -> Result<String, CodegenError> {
+		codegen::to_rust( &self.brain, self.think_ticks, &self.inputs, &self.outputs, module, false )
+	}
+
+	// Same, for no_std targets. Needs the libm crate for exp and sin (tanh, sigmoid, gaussian and sine activations), 
+	// which may round differently than std in the last bit.
+	pub fn to_rust_no_std(&self, module :&str) -> Result<String, CodegenError> {
+		codegen::to_rust( &self.brain, self.think_ticks, &self.inputs, &self.outputs, module, true )
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{World, Evaluation};
+	use crate::world::tests::{config, Line, Walker};
+
+	#[test]
+	fn matches_the_organism() {
+		for cfg in [config(), Config { evaluation: Evaluation::Feedforward, think_ticks: 2, ..config() }] {
+			let mut world :World<Line, Walker> = World::new( cfg.clone() );
+			for org in world.organisms.iter_mut() {
+				let mut net = Network::from_hex( &org.genome().to_hex(), &cfg ).unwrap();
+				org.creature.x = 0.5;
+				for step in 0..6 {
+					if step == 3 { // reset and start over from the same place, both sides
+						net.reset();
+						org.brain.reset();
+					}
+					let x = org.creature.x; // Walker senses x on every input
+					org.take_step( &mut world.environs, &cfg );
+					assert_eq!( net.step( &[x, x] ), &org.brain.output_values[..] );
+				}
+			}
+		}
+	}
+
+	// the files a run writes are enough, no config needed
+	#[test]
+	fn loads_saved_files() {
+		let cfg = Config { evaluation: Evaluation::Feedforward, think_ticks: 2, ..config() };
+		let mut world :World<Line, Walker> = World::new( cfg.clone() );
+		world.advance( 40 );
+		let dir = std::env::temp_dir().join( format!("gworld-network-{}", std::process::id()) );
+		std::fs::create_dir_all( &dir ).unwrap();
+		let fame = world.hall_of_fame();
+		fame.save( dir.join("best.json") ).unwrap();
+		world.save_population( dir.join("population.bin") ).unwrap();
+
+		let from_hex = |genome :&Genome| Network::from_hex( &genome.to_hex(), &cfg ).unwrap();
+		let mut expected = vec![ from_hex( &fame.best().unwrap().genome ) ];
+		expected.extend( world.organisms.iter().filter(|org| org.alive).map(|org| from_hex( org.genome() )) );
+		let mut loaded = vec![ Network::from_champion_file( dir.join("best.json") ).unwrap() ];
+		loaded.extend( Network::from_population_file( dir.join("population.bin") ).unwrap() );
+		std::fs::remove_dir_all( &dir ).ok();
+
+		assert_eq!( loaded.len(), expected.len() );
+		for (a, b) in loaded.iter_mut().zip( expected.iter_mut() ) {
+			for x in [0.1, 0.7, -0.3] {
+				assert_eq!( a.step( &[x, x] ), b.step( &[x, x] ) );
+			}
+		}
+	}
+}
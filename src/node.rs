@@ -1,6 +1,4 @@
-use super::Config;
-
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) enum NodeType {
     INPUT,
     HIDDEN,
@@ -24,14 +22,12 @@ impl Node {
     pub(crate) fn is_hidden(&self) -> bool { matches!(self.node_type, NodeType::HIDDEN) }
     pub(crate) fn is_input(&self) -> bool { matches!(self.node_type, NodeType::INPUT) }
     pub(crate) fn is_output(&self) -> bool { matches!(self.node_type, NodeType::OUTPUT) }
-    pub(crate) fn get_name(&self) -> String {
-		Config::get().node_name( self )
-    }
 }
 
 impl std::fmt::Debug for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.write_str( format!("{}", self.get_name()).as_str() )
+		// names live in the world's Config now, Brain looks them up when it's built
+		write!(f, "{}", self)
         // f.debug_tuple( "" ).field( &self.idx ).field( &self.node_type ).finish()
     }
 }
@@ -1,6 +1,6 @@
-use super::genes::Genome; // we need pub here to re-export, right?
+use super::genes::{Gene, Genome}; // we need pub here to re-export, right?
 use super::brains::Brain;
-use super::{math, Creature};
+use super::{Config, Creature, IndependentCreature, NodeMask, WorldRng};
 
 // Is there some way to make this whole fuckin file pub(crate), while letting the regular pub override that where noted?
 
@@ -13,6 +13,7 @@ pub struct Organism <T:Creature> {
 	pub alive: bool,
 	pub age: usize,
 	pub(crate) offspring: usize,
+	pub(crate) born: usize, // world clock at birth
 	pub(crate) max_fitness: f32,
 	pub(crate) fitness: f32,
 
@@ -22,9 +23,20 @@ pub struct Organism <T:Creature> {
 }
 
 impl <T:Creature + Creature<CCT = T>> Organism <T> { 
-	pub(crate) fn new( env :&mut T::Env ) -> Self {
-		let genome = Genome::new();
-		Organism::from_genome( genome, env, Vec::new() )
+	pub(crate) fn new( env :&mut T::Env, cfg :&Config, rng :&mut WorldRng ) -> Self {
+		let genome = Genome::new(cfg, rng);
+		Organism::from_genome( genome, env, Vec::new(), cfg )
+	}
+
+	pub fn genome(&self) -> &Genome {
+		&self.genome
+	}
+
+	// The brain as a Graphviz graph, named after the config's inputs and outputs. [See: World::to_dot]
+	// show_inactive adds the genes that didn't make it into the brain, dashed.
+	pub(crate) fn to_dot( &self, cfg :&Config, show_inactive :bool ) -> String {
+		let inactive :Vec<Gene> = if show_inactive { self.genome.inactive_genes().collect() } else { Vec::new() };
+		self.brain.to_dot( cfg, &inactive )
 	}
 
 	// that's fascinating.
@@ -32,87 +44,109 @@ impl <T:Creature + Creature<CCT = T>> Organism <T> {
 	// not that the organism does. It takes 2 to reproduce, (or more!). 
 	// It's never going to work 
 
-	pub(crate) fn from_genome( genome :Genome, env :&mut T::Env, parents :Vec<&T::CCT> ) -> Self {
+	pub(crate) fn from_genome( genome :Genome, env :&mut T::Env, parents :Vec<&T::CCT>, cfg :&Config ) -> Self {
 		Self {
 			//environs: env,
-			brain: Brain::new( &genome ), // need to build brain first appartently (oh rust)
+			brain: Brain::new( &genome, cfg ), // need to build brain first appartently (oh rust)
 			genome, 
 			creature: T::new( env, parents ),
 
-			alive: true, age: 0, offspring: 0, 
+			alive: true, age: 0, offspring: 0, born: 0,
+			fitness: 0., max_fitness: 0.,
+		}
+	}
+
+	// an existing creature, ie from a checkpoint. Doesn't touch the environment.
+	pub(crate) fn restore( genome :Genome, creature :T, cfg :&Config ) -> Self {
+		Self {
+			brain: Brain::new( &genome, cfg ),
+			genome, 
+			creature,
+
+			alive: true, age: 0, offspring: 0, born: 0,
 			fitness: 0., max_fitness: 0.,
 		}
 	}
 
-	pub(crate) fn bud( &self, env :&mut T::Env ) -> Self {
-		let mut parents = Vec::new();
-		parents.push( &self.creature );
-		return Self::from_genome( self.genome.bud(), env, parents )
+	pub(crate) fn bud( &self, env :&mut T::Env, cfg :&Config, rng :&mut WorldRng ) -> Self {
+		let parents = vec![ &self.creature ];
+		Self::from_genome( self.genome.bud(cfg, rng), env, parents, cfg )
+	}
+
+	// all the parents' creatures get passed along to Creature::new
+	pub(crate) fn breed( parents :&[&Self], env :&mut T::Env, cfg :&Config, rng :&mut WorldRng ) -> Self {
+		let genomes :Vec<&Genome> = parents.iter().map(|p| &p.genome).collect();
+		let creatures = parents.iter().map(|p| &p.creature).collect();
+		Self::from_genome( Genome::crossover( &genomes, cfg, rng ), env, creatures, cfg )
 	}
 
 // 	pub(crate) fn handle_result( &mut self, env :&T::Env ) {
 // 		self.set_outputs( env );
 // 	}
 
-	pub(crate) fn take_step( &mut self, env :&mut T::Env ) {
-		self.get_inputs( env );
-		self.process_inputs();
-		self.set_outputs( env );
-		self.take_action( env );
+	pub(crate) fn take_step( &mut self, env :&mut T::Env, cfg :&Config ) {
+		self.get_inputs( env, cfg );
+		self.process_inputs( cfg );
+		self.set_outputs( env, cfg );
+		self.take_action( env, cfg );
+	}
+
+	pub(crate) fn take_action( &mut self, env :&mut T::Env, cfg :&Config ) {
+		let fitness = self.creature.act( env );
+		self.score( fitness );
+		self.age( 1, env, cfg );
 	}
 
-	pub(crate) fn take_action( &mut self, env :&mut T::Env ) {
-		self.fitness = f32::max( 0.0001, self.creature.act( env )); // no negative fitness (for now, see note in World.reproduce)
+	fn score( &mut self, fitness :f32 ) {
+		self.fitness = f32::max( 0.0001, fitness ); // no negative fitness (for now, see note in World.reproduce)
 		self.max_fitness = f32::max( 
 			self.max_fitness, 
 			self.fitness,
 		);
-		self.age( 1, env );
 	}
 
 	// may cause death (is steps really necessary??)
-	fn age( &mut self, steps :usize, env :&mut T::Env ) {
+	fn age( &mut self, steps :usize, env :&mut T::Env, cfg :&Config ) {
 		self.age += steps;
 		// println!("Aging: {}", self.age);
-		if self.creature.die( self.age, self.fitness, env ) {
+		if self.creature.die( self.age, self.fitness, env, cfg ) {
 			// println!("Create is dieing");
 			self.alive = false;
 		}
 	}
 
-	pub(crate) fn set_outputs( &mut self, env :&T::Env ) {
-		for nuron in self.brain.outputs.iter() {
-			self.creature.tx_output( nuron.node.get_name().as_str(), nuron.state, env );
-		}
+	// masks and names were sorted out when the brain was built, so no lookups (or allocations) here
+	pub(crate) fn set_outputs( &mut self, env :&T::Env, cfg :&Config ) {
+		let mask = NodeMask::new( &self.brain.output_mask, &cfg.outputs );
+		self.creature.actuate( env, &self.brain.output_values, &mask );
 	}
 
-	pub(crate) fn get_inputs( &mut self, env :&T::Env ) {
-		for node in self.brain.inputs.iter() {
-			self.brain.node_state[ node.idx ] = self.creature.rx_input( &node.get_name(), env );
-		}
+	pub(crate) fn get_inputs( &mut self, env :&T::Env, cfg :&Config ) {
+		let mask = NodeMask::new( &self.brain.input_mask, &cfg.inputs );
+		let inputs = &mut self.brain.node_state[ ..cfg.inputs.len() ]; // inputs come first in node_state
+		self.creature.sense( env, inputs, &mask );
 	}
 
-    pub(crate) fn process_inputs( &mut self ) {
-		// first sum all nodes in hidden and output layer, using node_state from last live() iteration, plus with updated inputs of course
-		for nuron in self.brain.nurons.iter_mut().chain( self.brain.outputs.iter_mut() ) {
-			let mut state:f32 = 0f32;
-			for gene in nuron.incoming.iter() {
-				let s:f32 = gene.strength;
-				let value = self.brain.node_state[ gene.source.idx ];
-				state += value * s;
-			}
-        	nuron.state = state;
+	// Config.think_ticks rounds of propagation per step, so recurrent circuits can settle before acting [See: Brain::process]
+	pub(crate) fn process_inputs( &mut self, cfg :&Config ) {
+		for _tick in 0..cfg.think_ticks {
+			self.brain.process();
 		}
+	}
+}
 
-		// now write the values to the node_state
-		for nuron in self.brain.nurons.iter() {
-			self.brain.node_state[ nuron.node.idx ] = math::tanh( nuron.state ); // squash them down!
+// same step, with the environment shared (read only) between threads [See: World::par_advance]
+impl <T:IndependentCreature + Creature<CCT = T>> Organism <T> { 
+	#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+	pub(crate) fn take_step_alone( &mut self, env :&T::Env, cfg :&Config, rng :&mut WorldRng ) {
+		self.get_inputs( env, cfg );
+		self.process_inputs( cfg );
+		self.set_outputs( env, cfg );
+		let fitness = self.creature.act_alone( env, rng );
+		self.score( fitness );
+		self.age += 1;
+		if self.creature.die_alone( self.age, self.fitness, env, cfg ) {
+			self.alive = false;
 		}
-		
-		//// not really necessary... but we're doing it for completeness, maybe someone will expect it to be there in the future. :|
-		//for nuron in outputs.iter() {
-		//	node_state[ nuron.root.idx() ] = nuron.state; // don't squash outputs. We'll manually 
-		//}
-    }
+	}
 }
-
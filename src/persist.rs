@@ -0,0 +1,116 @@
+use super::Config;
+use super::fame::ChampionRecord;
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use std::path::Path;
+
+// Saving and loading what evolution produced. Genomes go to hex strings [See: Genome::to_hex], 
+// whole populations go to a file: bincode for .bin/.bincode, json for anything else.
+
+pub(crate) const POPULATION_VERSION :u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PopulationFile {
+	pub(crate) version :u32,
+	pub(crate) config :Config, // what the genomes were evolved with, so we know how to read them
+	pub(crate) genomes :Vec<String>, // hex
+}
+
+// HallOfFame::save, config included for the same reason
+pub(crate) const FAME_VERSION :u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct FameFile {
+	pub(crate) version :u32,
+	pub(crate) config :Config,
+	pub(crate) champions :Vec<ChampionRecord>,
+}
+
+// Everything a World needs to pick up exactly where it left off. 
+// Written with references (E = &Env), read back owned.
+pub(crate) const CHECKPOINT_VERSION :u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Checkpoint<E, T, R> {
+	pub(crate) version :u32,
+	pub(crate) config :Config,
+	pub(crate) seed :u64,
+	pub(crate) rng :R,
+	pub(crate) clock :usize,
+	pub(crate) extinct :bool, // already reported
+	pub(crate) extinctions :usize,
+	pub(crate) births :usize, // this step's, for stats()
+	pub(crate) deaths :usize,
+	pub(crate) fertile :Vec<Vec<usize>>,
+	pub(crate) hall_of_fame :Vec<ChampionRecord>,
+	pub(crate) organisms :Vec<OrganismState<T>>,
+	pub(crate) environs :E,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct OrganismState<T> {
+	pub(crate) genome :String, // hex
+	pub(crate) node_state :Vec<f32>, // the brain remembers last step's values
+	pub(crate) alive :bool,
+	pub(crate) age :usize,
+	pub(crate) offspring :usize,
+	pub(crate) born :usize,
+	pub(crate) fitness :f32,
+	pub(crate) max_fitness :f32,
+	pub(crate) creature :T,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PersistError {
+	Io { path :String, reason :String },
+	Parse(String), // file or hex doesn't parse
+	Version(String), // written by a format we don't know about
+	Mismatch(String), // doesn't fit the world's Config
+}
+
+impl std::fmt::Display for PersistError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			PersistError::Io { path, reason } => write!(f, "could not access {}: {}", path, reason),
+			PersistError::Parse(reason) => write!(f, "could not parse: {}", reason),
+			PersistError::Version(version) => write!(f, "unknown format version `{}`", version),
+			PersistError::Mismatch(reason) => write!(f, "doesn't match the config: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for PersistError {}
+
+// The saved genomes only make sense with the same nodes (and ploidy) they were evolved with
+pub(crate) fn check_compatible(saved :&Config, cfg :&Config) -> Result<(), PersistError> {
+	if saved.inputs != cfg.inputs { return Err( PersistError::Mismatch( format!("inputs were {:?}", saved.inputs) ) ) }
+	if saved.outputs != cfg.outputs { return Err( PersistError::Mismatch( format!("outputs were {:?}", saved.outputs) ) ) }
+	if saved.neurons != cfg.neurons { return Err( PersistError::Mismatch( format!("neurons were {}", saved.neurons) ) ) }
+	if saved.diploid != cfg.diploid { return Err( PersistError::Mismatch( format!("diploid was {}", saved.diploid) ) ) }
+	Ok(())
+}
+
+fn is_bincode(path :&Path) -> bool {
+	matches!( path.extension().and_then(|ext| ext.to_str()), Some("bin") | Some("bincode") )
+}
+
+fn io_error(path :&Path, e :impl std::fmt::Display) -> PersistError {
+	PersistError::Io { path: path.display().to_string(), reason: e.to_string() }
+}
+
+pub(crate) fn write_file<S :Serialize>(path :&Path, value :&S) -> Result<(), PersistError> {
+	let bytes = if is_bincode( path ) {
+		bincode::serialize( value ).map_err(|e| PersistError::Parse( e.to_string() ))?
+	} else {
+		serde_json::to_vec_pretty( value ).map_err(|e| PersistError::Parse( e.to_string() ))?
+	};
+	std::fs::write( path, bytes ).map_err(|e| io_error( path, e ))
+}
+
+pub(crate) fn read_file<D :DeserializeOwned>(path :&Path) -> Result<D, PersistError> {
+	let bytes = std::fs::read( path ).map_err(|e| io_error( path, e ))?;
+	if is_bincode( path ) {
+		bincode::deserialize( &bytes ).map_err(|e| PersistError::Parse( e.to_string() ))
+	} else {
+		serde_json::from_slice( &bytes ).map_err(|e| PersistError::Parse( e.to_string() ))
+	}
+}
@@ -0,0 +1,125 @@
+use super::stats::PopulationStats;
+use std::time::{Duration, Instant};
+
+// When World::run_until should stop. Chain as many as you like, first one hit wins.
+// ie: StopCriteria::new().target_fitness(0.95).stagnation(500).max_steps(100_000)
+#[derive(Default)]
+pub struct StopCriteria {
+	target_fitness :Option<f32>,
+	stagnation :Option<usize>,
+	max_steps :Option<usize>,
+	time_budget :Option<Duration>,
+	extinction :bool,
+	until :Option<StopCallback>,
+}
+
+type StopCallback = Box<dyn FnMut(&PopulationStats) -> bool>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+	TargetFitness, // someone alive reached it
+	Stagnation, // best fitness didn't improve for that many steps
+	MaxSteps,
+	TimeBudget,
+	Extinction, // everyone died (if asked to stop on it, or if nothing brought them back)
+	User, // the closure said so
+	NoCriteria, // nothing to stop on, so it didn't start
+}
+
+#[derive(Clone, Debug)]
+pub struct RunSummary {
+	pub reason :StopReason,
+	pub steps :usize, // taken by this run, not the world clock
+	pub best_fitness :f32, // best max_fitness seen among the living during the run
+	pub elapsed :Duration,
+	pub stats :PopulationStats, // where it ended up
+}
+
+impl StopCriteria {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// stop once a living organism's max_fitness gets here
+	pub fn target_fitness(mut self, fitness :f32) -> Self {
+		self.target_fitness = Some( fitness );
+		self
+	}
+
+	// stop after this many steps without a new best fitness
+	pub fn stagnation(mut self, steps :usize) -> Self {
+		self.stagnation = Some( steps );
+		self
+	}
+
+	pub fn max_steps(mut self, steps :usize) -> Self {
+		self.max_steps = Some( steps );
+		self
+	}
+
+	// wall clock, checked between steps (between batches when independent)
+	pub fn time_budget(mut self, budget :Duration) -> Self {
+		self.time_budget = Some( budget );
+		self
+	}
+
+	// stop on any extinction, even one the reseed policy rescued
+	pub fn extinction(mut self) -> Self {
+		self.extinction = true;
+		self
+	}
+
+	// checked after every step, return true to stop
+	pub fn until<F :FnMut(&PopulationStats) -> bool + 'static>(mut self, stop :F) -> Self {
+		self.until = Some( Box::new( stop ) );
+		self
+	}
+
+	pub(crate) fn is_unbounded(&self) -> bool {
+		self.target_fitness.is_none() && self.stagnation.is_none() && self.max_steps.is_none() 
+			&& self.time_budget.is_none() && !self.extinction && self.until.is_none()
+	}
+
+	// the most steps we can take before checking again
+	pub(crate) fn steps_left(&self, taken :usize) -> usize {
+		self.max_steps.map_or( usize::MAX, |max| max.saturating_sub(taken) )
+	}
+}
+
+// Keeps score while World::run_until goes
+pub(crate) struct RunState {
+	pub(crate) steps :usize,
+	pub(crate) best_fitness :f32,
+	last_improved :usize, // steps, when best_fitness last went up
+	start :Instant,
+}
+
+impl RunState {
+	pub(crate) fn new(stats :&PopulationStats) -> Self {
+		let best_fitness = if stats.living > 0 { stats.max } else { f32::MIN };
+		Self { steps: 0, best_fitness, last_improved: 0, start: Instant::now() }
+	}
+
+	// after each step (or batch), extinct: went extinct during it
+	pub(crate) fn check(&mut self, criteria :&mut StopCriteria, stats :&PopulationStats, extinct :bool) -> Option<StopReason> {
+		if stats.living > 0 && stats.max > self.best_fitness {
+			self.best_fitness = stats.max;
+			self.last_improved = self.steps;
+		}
+
+		if criteria.is_unbounded() { return Some( StopReason::NoCriteria ) }
+		if stats.living == 0 || (extinct && criteria.extinction) { return Some( StopReason::Extinction ) }
+		if criteria.target_fitness.is_some_and(|target| stats.max >= target) { return Some( StopReason::TargetFitness ) }
+		if let Some(until) = criteria.until.as_mut() {
+			if until( stats ) { return Some( StopReason::User ) }
+		}
+		if criteria.stagnation.is_some_and(|n| self.steps - self.last_improved >= n) { return Some( StopReason::Stagnation ) }
+		if criteria.max_steps.is_some_and(|max| self.steps >= max) { return Some( StopReason::MaxSteps ) }
+		if criteria.time_budget.is_some_and(|budget| self.start.elapsed() >= budget) { return Some( StopReason::TimeBudget ) }
+		None
+	}
+
+	pub(crate) fn summary(self, reason :StopReason, stats :PopulationStats) -> RunSummary {
+		RunSummary { reason, steps: self.steps, best_fitness: self.best_fitness, elapsed: self.start.elapsed(), stats }
+	}
+}
@@ -0,0 +1,208 @@
+use rand::{Rng, RngCore};
+
+// Picks which organisms get to reproduce. [See: docs/repro.txt]
+// The world hands over the max_fitness of every living organism, and gets back indexes into that slice.
+// The same organism can win more than once, that's just more babies for it.
+pub trait SelectionStrategy {
+	fn select(&mut self, fitness :&[f32], count :usize, rng :&mut dyn RngCore) -> Vec<usize>;
+}
+
+// Fitness proportionate lottery. The world's default, and the original behavior. 
+// A fitness twice as large gets twice the tickets. Negative fitness counts as positive (abs).
+pub struct Roulette;
+
+impl SelectionStrategy for Roulette {
+	fn select(&mut self, fitness :&[f32], count :usize, rng :&mut dyn RngCore) -> Vec<usize> {
+		let sum = f32::max( fitness.iter().map(|f| f32::abs(*f)).sum(), 0.001 );
+		let mut winners = Vec::new();
+		for _i in 0..count {
+			// Pick a number, 0 - sum(fitness)
+			let num = rng.gen_range(0.0..sum);
+
+			// Then just cycle through the fitness until we find the "winner"
+			// (there might not be one, if everybody's fitness is ~0)
+			let mut tot = 0.;
+			for (id, f) in fitness.iter().enumerate() {
+				tot += f32::abs(*f); // abs: sanity check, could shift all numbers by greatest negative number... 
+				if tot >= num { // We have a winner
+					winners.push( id );
+					break;
+				}
+			}
+		}
+		winners
+	}
+}
+
+// Pick k at random, the fittest of them wins. Bigger k, more pressure. 
+pub struct Tournament {
+	pub k :usize,
+}
+
+impl SelectionStrategy for Tournament {
+	fn select(&mut self, fitness :&[f32], count :usize, rng :&mut dyn RngCore) -> Vec<usize> {
+		if fitness.is_empty() { return Vec::new() }
+		(0..count).map(|_| {
+			let mut best = rng.gen_range(0..fitness.len());
+			for _i in 1..self.k {
+				let other = rng.gen_range(0..fitness.len());
+				if fitness[other] > fitness[best] { best = other; }
+			}
+			best
+		}).collect()
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum RankPressure {
+	// 1.0 (no pressure) to 2.0 (the worst never wins). Chances fall off linearly with rank.
+	Linear(f32),
+	// 0.0 - 1.0, each rank down gets this fraction of the chances of the one above it. 
+	Exponential(f32),
+	// (anything outside those ranges gets clamped, past them the pressure would flip and favor the worst)
+}
+
+// Lottery on rank instead of raw fitness, so only the order matters. 
+// Doesn't care about negative, tiny or huge fitness values.
+pub struct Rank {
+	pub pressure :RankPressure,
+}
+
+impl SelectionStrategy for Rank {
+	fn select(&mut self, fitness :&[f32], count :usize, rng :&mut dyn RngCore) -> Vec<usize> {
+		let n = fitness.len();
+		let order = ranked( fitness ); // worst first
+		let weights :Vec<f32> = (0..n).map(|rank| match self.pressure {
+			RankPressure::Linear(s) => if n > 1 {
+				let s = s.clamp( 1., 2. );
+				(2. - s) / n as f32 + 2. * rank as f32 * (s - 1.) / (n * (n - 1)) as f32
+			} else { 1. },
+			RankPressure::Exponential(c) => c.clamp( 0., 1. ).powi( (n - 1 - rank) as i32 ),
+		}).collect();
+		Roulette.select( &weights, count, rng ).into_iter().map(|rank| order[rank]).collect()
+	}
+}
+
+// Only the top fraction gets to breed, all with equal chances. 
+pub struct Truncation {
+	pub fraction :f32,
+}
+
+impl SelectionStrategy for Truncation {
+	fn select(&mut self, fitness :&[f32], count :usize, rng :&mut dyn RngCore) -> Vec<usize> {
+		if fitness.is_empty() { return Vec::new() }
+		let order = ranked( fitness );
+		let keep = usize::clamp( (self.fraction * fitness.len() as f32).ceil() as usize, 1, fitness.len() );
+		let top = &order[ order.len() - keep.. ];
+		(0..count).map(|_| top[ rng.gen_range(0..top.len()) ]).collect()
+	}
+}
+
+// Roulette with one spin and `count` evenly spaced pointers. 
+// Same odds as Roulette, but the number of wins stays close to what you'd expect. 
+pub struct StochasticUniversal;
+
+impl SelectionStrategy for StochasticUniversal {
+	fn select(&mut self, fitness :&[f32], count :usize, rng :&mut dyn RngCore) -> Vec<usize> {
+		if fitness.is_empty() || count == 0 { return Vec::new() }
+		let sum :f32 = fitness.iter().map(|f| f32::abs(*f)).sum();
+		if sum <= 0. { // nobody has any fitness, so everybody gets a turn
+			return (0..count).map(|i| i % fitness.len()).collect()
+		}
+		let spacing = sum / count as f32;
+		let start = rng.gen_range(0.0..spacing);
+
+		let mut winners = Vec::new();
+		let mut tot = 0.;
+		let mut id = 0;
+		for i in 0..count {
+			let pointer = start + i as f32 * spacing;
+			while id < fitness.len() - 1 && tot + f32::abs(fitness[id]) < pointer {
+				tot += f32::abs(fitness[id]);
+				id += 1;
+			}
+			winners.push( id );
+		}
+		winners
+	}
+}
+
+// indexes sorted by fitness, worst to best
+fn ranked(fitness :&[f32]) -> Vec<usize> {
+	let mut order :Vec<usize> = (0..fitness.len()).collect();
+	order.sort_by(|a, b| fitness[*a].total_cmp( &fitness[*b] ));
+	order
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	fn counts(strategy :&mut dyn SelectionStrategy, fitness :&[f32], count :usize) -> Vec<usize> {
+		let mut rng = ChaCha8Rng::seed_from_u64( 1 );
+		let mut counts = vec![0; fitness.len()];
+		for id in strategy.select( fitness, count, &mut rng ) {
+			counts[id] += 1;
+		}
+		counts
+	}
+
+	#[test]
+	fn roulette() {
+		assert_eq!( counts( &mut Roulette, &[0., 0., 5.], 10 ), vec![0, 0, 10] );
+		let c = counts( &mut Roulette, &[1., 3.], 1000 );
+		assert!( c[1] > 2 * c[0] );
+		// Everyone at ~0 and the lottery has no winner (extinction territory)
+		assert!( counts( &mut Roulette, &[0., 0.], 10 ).iter().sum::<usize>() < 10 );
+	}
+
+	#[test]
+	fn tournament() {
+		assert_eq!( counts( &mut Tournament { k: 64 }, &[1., 2., 3.], 20 ), vec![0, 0, 20] );
+		let c = counts( &mut Tournament { k: 1 }, &[1., 2., 3.], 300 ); // k=1 is just a random pick
+		assert!( c.iter().all(|n| *n > 50) );
+	}
+
+	#[test]
+	fn rank() {
+		// only the order matters, negative fitness is fine
+		let fitness = [-5., -1., -3.];
+		let c = counts( &mut Rank { pressure: RankPressure::Linear(2.0) }, &fitness, 300 );
+		assert_eq!( c[0], 0 ); // worst never wins at full pressure
+		assert!( c[1] > c[2] );
+		let c = counts( &mut Rank { pressure: RankPressure::Exponential(0.1) }, &fitness, 300 );
+		assert!( c[1] > c[2] && c[2] > c[0] );
+	}
+
+	#[test]
+	fn rank_pressure_clamped() {
+		let fitness = [1., 2., 3.];
+		let full = counts( &mut Rank { pressure: RankPressure::Linear(2.0) }, &fitness, 300 );
+		assert_eq!( counts( &mut Rank { pressure: RankPressure::Linear(5.0) }, &fitness, 300 ), full );
+		let none = counts( &mut Rank { pressure: RankPressure::Linear(1.0) }, &fitness, 300 );
+		assert_eq!( counts( &mut Rank { pressure: RankPressure::Linear(-3.0) }, &fitness, 300 ), none );
+
+		let c = counts( &mut Rank { pressure: RankPressure::Exponential(4.0) }, &fitness, 300 ); // as good as 1.0, no pressure
+		assert!( c.iter().all(|n| *n > 50) );
+		let c = counts( &mut Rank { pressure: RankPressure::Exponential(-0.5) }, &fitness, 300 ); // 0.0, only the best
+		assert_eq!( c, vec![0, 0, 300] );
+	}
+
+	#[test]
+	fn truncation() {
+		assert_eq!( counts( &mut Truncation { fraction: 0.25 }, &[4., 1., 3., 2.], 10 ), vec![10, 0, 0, 0] );
+		let c = counts( &mut Truncation { fraction: 0.5 }, &[4., 1., 3., 2.], 100 );
+		assert_eq!( (c[1], c[3]), (0, 0) );
+		assert_eq!( c[0] + c[2], 100 );
+	}
+
+	#[test]
+	fn stochastic_universal() {
+		// exactly the expected number of wins when they come out even
+		assert_eq!( counts( &mut StochasticUniversal, &[1., 1., 2.], 4 ), vec![1, 1, 2] );
+		assert_eq!( counts( &mut StochasticUniversal, &[0., 3., 0., 1.], 8 ), vec![0, 6, 0, 2] );
+		assert_eq!( counts( &mut StochasticUniversal, &[0., 0.], 4 ), vec![2, 2] );
+	}
+}
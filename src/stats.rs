@@ -0,0 +1,135 @@
+use serde::{Serialize, Deserialize};
+use std::io::Write;
+use std::path::Path;
+
+// Numbers about the living, for dashboards and regression checks. [See: World::stats]
+// Fitness here is each organism's max_fitness, same as the offspring lottery uses.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PopulationStats {
+	pub step :usize, // world clock
+	pub living :usize,
+	pub births :usize, // during the last step (or batch of steps, when independent)
+	pub deaths :usize,
+	pub min :f32,
+	pub max :f32,
+	pub mean :f32,
+	pub median :f32,
+	pub std_dev :f32,
+	pub mean_age :f32,
+	pub mean_active_genes :f32,
+	pub mean_brain_size :f32, // active nodes: inputs, hidden neurons and outputs
+}
+
+impl PopulationStats {
+	// per organism: (max_fitness, age, active genes, brain size)
+	pub(crate) fn collect<I :Iterator<Item = (f32, usize, usize, usize)>>(living :I) -> Self {
+		let mut fitness = Vec::new();
+		let (mut age, mut genes, mut brain) = (0, 0, 0);
+		for (f, a, g, b) in living {
+			fitness.push( f );
+			age += a;
+			genes += g;
+			brain += b;
+		}
+		if fitness.is_empty() { return Self::default() }
+
+		let n = fitness.len() as f32;
+		fitness.sort_by(|a, b| a.total_cmp(b));
+		let mean = fitness.iter().sum::<f32>() / n;
+		let mid = fitness.len() / 2;
+		let median = if fitness.len() % 2 == 0 { (fitness[mid - 1] + fitness[mid]) / 2. } else { fitness[mid] };
+		let variance = fitness.iter().map(|f| (f - mean) * (f - mean)).sum::<f32>() / n;
+		Self {
+			living: fitness.len(),
+			min: fitness[0],
+			max: fitness[fitness.len() - 1],
+			mean, median,
+			std_dev: variance.sqrt(),
+			mean_age: age as f32 / n,
+			mean_active_genes: genes as f32 / n,
+			mean_brain_size: brain as f32 / n,
+			..Self::default()
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatsFormat {
+	Csv, // header row, then one row per record
+	JsonLines, // one json object per line
+}
+
+// Appends a PopulationStats record every step. [See: World::record_stats]
+pub struct StatsRecorder {
+	out :Box<dyn Write>,
+	format :StatsFormat,
+	header :bool, // still owed a csv header
+}
+
+const CSV_HEADER :&str = "step,living,births,deaths,min,max,mean,median,std_dev,mean_age,mean_active_genes,mean_brain_size";
+
+impl StatsRecorder {
+	pub fn new<W :Write + 'static>(out :W, format :StatsFormat) -> Self {
+		Self { out: Box::new( out ), format, header: format == StatsFormat::Csv }
+	}
+
+	// Appends to the file (so a resumed run carries on the same one). The csv header is only written to an empty file.
+	pub fn csv<P :AsRef<Path>>(path :P) -> std::io::Result<Self> {
+		Self::open( path.as_ref(), StatsFormat::Csv )
+	}
+
+	pub fn json_lines<P :AsRef<Path>>(path :P) -> std::io::Result<Self> {
+		Self::open( path.as_ref(), StatsFormat::JsonLines )
+	}
+
+	fn open(path :&Path, format :StatsFormat) -> std::io::Result<Self> {
+		let file = std::fs::OpenOptions::new().create(true).append(true).open( path )?;
+		let empty = file.metadata()?.len() == 0;
+		let mut recorder = Self::new( std::io::BufWriter::new( file ), format );
+		recorder.header &= empty;
+		Ok( recorder )
+	}
+
+	pub fn record(&mut self, s :&PopulationStats) -> std::io::Result<()> {
+		match self.format {
+			StatsFormat::Csv => {
+				if self.header {
+					writeln!( self.out, "{}", CSV_HEADER )?;
+					self.header = false;
+				}
+				writeln!( self.out, "{},{},{},{},{},{},{},{},{},{},{},{}", 
+					s.step, s.living, s.births, s.deaths, s.min, s.max, s.mean, s.median, s.std_dev, 
+					s.mean_age, s.mean_active_genes, s.mean_brain_size )?;
+			},
+			StatsFormat::JsonLines => {
+				serde_json::to_writer( &mut self.out, s )?;
+				writeln!( self.out )?;
+			},
+		}
+		Ok(())
+	}
+
+	pub fn flush(&mut self) -> std::io::Result<()> {
+		self.out.flush()
+	}
+}
+
+impl Drop for StatsRecorder {
+	fn drop(&mut self) {
+		self.out.flush().ok();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn collects() {
+		let stats = PopulationStats::collect( [(1., 2, 10, 4), (3., 4, 20, 6), (2., 6, 30, 8), (6., 8, 40, 10)].into_iter() );
+		assert_eq!( (stats.living, stats.min, stats.max, stats.mean, stats.median), (4, 1., 6., 3., 2.5) );
+		assert!( (stats.std_dev - 1.870829).abs() < 1e-5 );
+		assert_eq!( (stats.mean_age, stats.mean_active_genes, stats.mean_brain_size), (5., 25., 7.) );
+		assert_eq!( PopulationStats::collect( std::iter::empty() ), PopulationStats::default() );
+	}
+}
@@ -0,0 +1,164 @@
+use super::{Config, Creature, NodeMask};
+
+// Inputs and outputs as enums instead of strings, so a misspelled input is a compile error.
+// #[derive(Sensors)] / #[derive(Actions)] (the derive feature, on by default) fill these in from the variants, 
+// and ConfigBuilder::sensors / actions put the same names in the config. 
+// ie: #[derive(Clone, Copy, Sensors)] enum Sense { X, Y, #[gworld(name = "DT")] DistTop }
+pub trait Sensors :Copy + 'static {
+	const NAMES :&'static [&'static str]; // in Config.inputs order
+	fn from_index(index :usize) -> Self;
+	fn index(self) -> usize;
+
+	fn from_name(name :&str) -> Option<Self> {
+		Self::NAMES.iter().position(|n| *n == name).map(Self::from_index)
+	}
+}
+
+pub trait Actions :Copy + 'static {
+	const NAMES :&'static [&'static str]; // in Config.outputs order
+	fn from_index(index :usize) -> Self;
+	fn index(self) -> usize;
+
+	fn from_name(name :&str) -> Option<Self> {
+		Self::NAMES.iter().position(|n| *n == name).map(Self::from_index)
+	}
+}
+
+// Creature, with the enums. Anything that's a TypedCreature is a Creature, so it goes straight into a World. 
+// The World makes sure the config's inputs/outputs are the enums' names (in order).
+pub trait TypedCreature :Sized {
+	type Env; // user supplied environment
+	type Sensor :Sensors;
+	type Action :Actions;
+	fn new(env :&mut Self::Env, parents :Vec<&Self>) -> Self;
+	fn rx_sensor(&self, sensor :Self::Sensor, env :&Self::Env) -> f32;
+	fn tx_action(&mut self, action :Self::Action, value :f32, env :&Self::Env);
+	fn act(&mut self, env :&mut Self::Env) -> f32; // returns fitness
+
+	fn die(&self, age :usize, _fitness :f32, _env :&mut Self::Env, cfg :&Config) -> bool { 
+		age > cfg.lifespan
+	}
+}
+
+impl <C :TypedCreature> Creature for C {
+	type Env = C::Env;
+	type CCT = C;
+	const INPUTS :Option<&'static [&'static str]> = Some( C::Sensor::NAMES );
+	const OUTPUTS :Option<&'static [&'static str]> = Some( C::Action::NAMES );
+
+	fn new(env :&mut Self::Env, parents :Vec<&Self::CCT>) -> Self {
+		TypedCreature::new( env, parents )
+	}
+
+	// the world checked the names against the config (in try_new and resume), so these always match
+	fn rx_input(&self, input :&str, env :&Self::Env) -> f32 {
+		let sensor = C::Sensor::from_name( input ).expect("input isn't one of the creature's sensors");
+		self.rx_sensor( sensor, env )
+	}
+
+	fn tx_output(&mut self, output :&str, value :f32, env :&Self::Env) {
+		let action = C::Action::from_name( output ).expect("output isn't one of the creature's actions");
+		self.tx_action( action, value, env )
+	}
+
+	// by index, no names involved
+	fn sense(&self, env :&Self::Env, out :&mut [f32], mask :&NodeMask) {
+		for i in mask.active() {
+			out[i] = self.rx_sensor( C::Sensor::from_index(i), env );
+		}
+	}
+
+	fn actuate(&mut self, env :&Self::Env, outputs :&[f32], mask :&NodeMask) {
+		for i in mask.active() {
+			self.tx_action( C::Action::from_index(i), outputs[i], env );
+		}
+	}
+
+	fn act(&mut self, env :&mut Self::Env) -> f32 {
+		TypedCreature::act( self, env )
+	}
+
+	fn die(&self, age :usize, fitness :f32, env :&mut Self::Env, cfg :&Config) -> bool {
+		TypedCreature::die( self, age, fitness, env, cfg )
+	}
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+	use super::*;
+	use crate::{World, Environs, ConfigError, Sensors, Actions};
+
+	#[derive(Clone, Copy, Debug, PartialEq, Sensors)]
+	enum Sense { X, #[gworld(name = "ONE")] One }
+
+	#[derive(Clone, Copy, Debug, PartialEq, Actions)]
+	enum Act { #[gworld(name = "MOVX")] MoveX }
+
+	#[derive(serde::Serialize, serde::Deserialize)]
+	struct Track {}
+	impl Environs for Track {
+		type Creature = Runner;
+		fn new() -> Self { Self {} }
+	}
+
+	#[derive(serde::Serialize, serde::Deserialize)]
+	struct Runner { x :f32 }
+	impl TypedCreature for Runner {
+		type Env = Track;
+		type Sensor = Sense;
+		type Action = Act;
+		fn new( _env :&mut Track, _parents :Vec<&Self> ) -> Self { Self { x: 0. } }
+		fn rx_sensor( &self, sensor :Sense, _env :&Track ) -> f32 {
+			match sensor {
+				Sense::X => self.x,
+				Sense::One => 1.,
+			}
+		}
+		fn tx_action( &mut self, action :Act, value :f32, _env :&Track ) {
+			match action {
+				Act::MoveX => self.x += crate::math::tanh( value ),
+			}
+		}
+		fn act( &mut self, _env :&mut Track ) -> f32 { f32::abs( self.x ) }
+	}
+
+	#[test]
+	fn derived_names() {
+		assert_eq!( Sense::NAMES, ["X", "ONE"] );
+		assert_eq!( Act::NAMES, ["MOVX"] );
+		assert_eq!( (Sense::from_index(1), Sense::One.index()), (Sense::One, 1) );
+		assert_eq!( Sense::from_name("ONE"), Some(Sense::One) );
+		assert_eq!( Sense::from_name("one"), None );
+	}
+
+	#[test]
+	fn typed_world() {
+		let config = Config { population: 10, seed: Some(3), ..crate::world::tests::config() };
+		let cfg = Config::builder().sensors::<Sense>().actions::<Act>().neurons(3).population(10).seed(3).build().unwrap();
+		assert_eq!( (&cfg.inputs, &cfg.outputs), (&config.inputs, &config.outputs) );
+
+		let mut world :World<Track, Runner> = World::new( cfg );
+		world.advance( 30 );
+		assert!( world.stats().max > 0. );
+
+		let swapped = Config { inputs: vec!["ONE".into(), "X".into()], ..config };
+		let err = World::<Track, Runner>::try_new( swapped ).err().unwrap();
+		assert!( matches!(err, ConfigError::Invalid { field: "inputs", .. }) );
+	}
+
+	// say the enum got reordered after the checkpoint was written
+	#[test]
+	fn resume_checks_names() {
+		use crate::world::tests::{config, Line, Walker};
+		let path = std::env::temp_dir().join( format!("gworld-typed-{}.json", std::process::id()) );
+		for (inputs, fits) in [(["ONE", "X"], false), (["X", "ONE"], true)] {
+			let world :World<Line, Walker> = World::new( Config { inputs: inputs.map(String::from).to_vec(), ..config() } );
+			world.checkpoint( &path ).unwrap();
+			match World::<Track, Runner>::resume( &path ) {
+				Ok(_) => assert!( fits ),
+				Err(e) => assert!( !fits && matches!(e, crate::PersistError::Mismatch(_)), "{}", e ),
+			}
+		}
+		std::fs::remove_file( &path ).ok();
+	}
+}
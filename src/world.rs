@@ -1,11 +1,61 @@
 use super::organism::Organism;
-use super::Config;
-use rand::Rng;
+use super::{Config, ConfigError, Verbosity};
+use super::selection::{SelectionStrategy, Roulette};
+use super::genes::Genome;
+use super::fame::HallOfFame;
+use super::stats::{PopulationStats, StatsRecorder};
+use super::run::{StopCriteria, RunState, RunSummary};
+use super::persist::{self, PersistError, PopulationFile, POPULATION_VERSION, Checkpoint, OrganismState, CHECKPOINT_VERSION};
+use serde::{Serialize, de::DeserializeOwned};
+use std::path::Path;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// All the randomness in a world comes from here, so a seed reproduces a whole run.
+pub type WorldRng = ChaCha8Rng;
 
 pub struct World<E:Environs, T:Creature> {
 	pub organisms :Vec<Organism<T>>,
 	pub environs :E,
-	fertile :Vec<usize>, // usize indexes into self.organisms 
+	config :Config, // each world gets its own settings
+	seed :u64,
+	rng :WorldRng,
+	clock :usize, // total steps taken
+	selection :Box<dyn SelectionStrategy>,
+	fame :HallOfFame, // the best of the dead
+	extinct :bool, // already reported, don't keep crying about it
+	extinctions :usize, // times it happened, rescued or not
+	on_extinction :Option<ExtinctionCallback>,
+	births :usize, // during the current step (batch, if independent)
+	deaths :usize,
+	recorder :Option<StatsRecorder>,
+	fertile :Vec<Vec<usize>>, // parents for each baby, usize indexes into self.organisms 
+}
+
+// How to bring the population back up when it drops below Config.min_population, or dies out entirely
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reseed {
+	None, // let it die (you'll still hear about it)
+	Random, // fresh random genomes, like at the start
+	HallOfFame, // clones of the best-ever genomes (random if the hall is empty)
+}
+
+// Handed to the World::on_extinction callback
+#[derive(Copy, Clone, Debug)]
+pub struct Extinction {
+	pub step :usize, // world clock when it happened
+	pub reseeded :usize, // how many organisms were brought in to replace them
+}
+
+type ExtinctionCallback = Box<dyn FnMut(&Extinction)>;
+
+fn check_names(field :&'static str, names :&[String], expected :Option<&[&str]>) -> Result<(), ConfigError> {
+	match expected {
+		Some(expected) if names.iter().map(String::as_str).ne( expected.iter().copied() ) => 
+			Err( ConfigError::Invalid { field, reason: format!("{:?} doesn't match the creature's {:?}", names, expected) } ),
+		_ => Ok(()),
+	}
 }
 
 pub trait Environs { // [See: docs/environs.txt]
@@ -16,10 +66,31 @@ pub trait Environs { // [See: docs/environs.txt]
 pub trait Creature {
 	type Env; // user supplied environment
 	type CCT; // user supplied creature type
+	// If the creature knows its node names (ie: a TypedCreature), the world won't run with a config that disagrees
+	const INPUTS :Option<&'static [&'static str]> = None;
+	const OUTPUTS :Option<&'static [&'static str]> = None;
+
 	fn new(env :&mut Self::Env, parents :Vec<&Self::CCT>) -> Self; // parents :&CCT
+	fn act(&mut self, env :&mut Self::Env) -> f32; // returns fitness
+
+	// One node at a time, by name
 	fn rx_input(&self, input :&str, env :&Self::Env) -> f32;
 	fn tx_output(&mut self, output :&str, value :f32, env :&Self::Env);
-	fn act(&mut self, env :&mut Self::Env) -> f32; // returns fitness
+
+	// What the world actually calls. By default one rx_input/tx_output per node, override them to do it all at once,
+	// in Config.inputs/outputs order, so shared work (distances etc) only happens once per step.
+	// Only the nodes in the mask are wired into the brain: skip the rest if they're expensive, nobody reads them.
+	fn sense(&self, env :&Self::Env, out :&mut [f32], mask :&NodeMask) {
+		for i in mask.active() {
+			out[i] = self.rx_input( mask.name(i), env );
+		}
+	}
+	// outputs not in the mask are 0
+	fn actuate(&mut self, env :&Self::Env, outputs :&[f32], mask :&NodeMask) {
+		for i in mask.active() {
+			self.tx_output( mask.name(i), outputs[i], env );
+		}
+	}
 	
 //	fn make_child(&self, env :&mut Self::Env, with :Vec<&Self::CCT>) -> Self;
 //	{ //, _parents :&Self::CCT) -> Self {
@@ -27,19 +98,175 @@ pub trait Creature {
 //	}
 
 	// let user redefine. Might want to make it some probability based on fitness and/or age, etc. 
-	fn die(&self, age :usize, _fitness :f32, _env :&mut Self::Env) -> bool { 
-		age > Config::get().lifespan
+	fn die(&self, age :usize, _fitness :f32, _env :&mut Self::Env, cfg :&Config) -> bool { 
+		age > cfg.lifespan
+	}
+}
+
+// Which of the inputs (or outputs) a brain actually uses. Indexes follow Config.inputs/outputs.
+pub struct NodeMask<'a> {
+	active :&'a [bool],
+	names :&'a [String],
+}
+
+impl <'a> NodeMask<'a> {
+	pub(crate) fn new(active :&'a [bool], names :&'a [String]) -> Self {
+		Self { active, names }
+	}
+
+	pub fn len(&self) -> usize {
+		self.active.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.active.is_empty()
+	}
+
+	pub fn is_active(&self, index :usize) -> bool {
+		self.active[index]
+	}
+
+	pub fn name(&self, index :usize) -> &'a str {
+		&self.names[index]
+	}
+
+	// indexes of the active nodes
+	pub fn active(&self) -> impl Iterator<Item = usize> + 'a {
+		self.active.iter().enumerate().filter(|(_, active)| **active).map(|(i, _)| i)
+	}
+}
+
+// For Config.independent worlds: creatures that only need to read the environment, so they can be stepped side by side. [See: World::par_advance]
+// rng is the organism's own stream, seeded from the world rng, so a run comes out the same however many threads there are.
+pub trait IndependentCreature :Creature {
+	fn act_alone(&mut self, env :&Self::Env, rng :&mut WorldRng) -> f32; // returns fitness
+	fn die_alone(&self, age :usize, _fitness :f32, _env :&Self::Env, cfg :&Config) -> bool { 
+		age > cfg.lifespan
 	}
 }
 
 impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
-	pub fn new() -> Self {
+	// panics on a bad config, use try_new if you'd rather handle it
+	pub fn new( config :Config ) -> Self {
+		match Self::try_new( config ) {
+			Ok(world) => world,
+			Err(e) => panic!("{}", e),
+		}
+	}
+
+	pub fn try_new( config :Config ) -> Result<Self, ConfigError> {
+		config.validate()?;
+		check_names( "inputs", &config.inputs, T::INPUTS )?;
+		check_names( "outputs", &config.outputs, T::OUTPUTS )?;
+		let seed = config.seed.unwrap_or_else( rand::random ); // no seed? make one up, but remember it
+		let mut rng = WorldRng::seed_from_u64( seed );
+		let fame = HallOfFame::new( &config );
 		let mut env = E::new();
-		Self { 
-			organisms: (0..Config::get().population).map(|_| Organism::new( &mut env ) ).collect(), 
+		Ok( Self { 
+			organisms: (0..config.population).map(|_| Organism::new( &mut env, &config, &mut rng ) ).collect(), 
 			environs: env,
+			config,
+			seed,
+			rng,
+			clock: 0,
+			selection: Box::new( Roulette ),
+			fame,
+			extinct: false,
+			extinctions: 0,
+			on_extinction: None,
+			births: 0,
+			deaths: 0,
+			recorder: None,
 			fertile: Vec::new(),
+		})
+	}
+
+	pub fn config(&self) -> &Config {
+		&self.config
+	}
+
+	// Put this in Config.seed to replay the run
+	pub fn seed(&self) -> u64 {
+		self.seed
+	}
+
+	// How many steps the world has taken so far
+	pub fn clock(&self) -> usize {
+		self.clock
+	}
+
+	// Writes the genomes of all the living. .bin/.bincode for bincode, otherwise json. 
+	pub fn save_population<P :AsRef<Path>>(&self, path :P) -> Result<(), PersistError> {
+		let file = PopulationFile {
+			version: POPULATION_VERSION,
+			config: self.config.clone(),
+			genomes: self.organisms.iter().filter(|org| org.alive).map(|org| org.genome.to_hex()).collect(),
+		};
+		persist::write_file( path.as_ref(), &file )
+	}
+
+	// Replaces every organism with the saved genomes, and brand new creatures (no parents) to carry them
+	pub fn load_population<P :AsRef<Path>>(&mut self, path :P) -> Result<(), PersistError> {
+		let file :PopulationFile = persist::read_file( path.as_ref() )?;
+		if file.version != POPULATION_VERSION {
+			return Err( PersistError::Version( file.version.to_string() ) )
+		}
+		persist::check_compatible( &file.config, &self.config )?;
+		let genomes = file.genomes.iter()
+			.map(|hex| Genome::from_hex( hex, &self.config ))
+			.collect::<Result<Vec<Genome>, PersistError>>()?;
+		self.fertile.clear();
+		self.organisms = genomes.into_iter()
+			.map(|genome| Organism::from_genome( genome, &mut self.environs, Vec::new(), &self.config ))
+			.collect();
+		self.extinct = self.organisms.is_empty();
+		Ok(())
+	}
+
+	// The best genomes ever seen, dead or alive. Best first, up to Config.hall_of_fame of them.
+	pub fn hall_of_fame(&self) -> HallOfFame {
+		let mut fame = self.fame.clone();
+		for org in self.organisms.iter().filter(|org| org.alive) {
+			fame.consider( org );
 		}
+		fame
+	}
+
+	// Organism id's brain as a Graphviz graph, nodes named after this world's inputs and outputs.
+	// show_inactive adds the genes that didn't make it into the brain, dashed. `dot -Tsvg brain.dot > brain.svg` to look at it.
+	pub fn to_dot(&self, id :usize, show_inactive :bool) -> String {
+		self.organisms[id].to_dot( &self.config, show_inactive )
+	}
+
+	// Called whenever every last organism is dead (after Config.reseed had its go)
+	pub fn on_extinction<F :FnMut(&Extinction) + 'static>(&mut self, callback :F) {
+		self.on_extinction = Some( Box::new( callback ) );
+	}
+
+	// Appends self.stats() after every step (every batch, when independent)
+	// ie: world.record_stats( StatsRecorder::csv("stats.csv")? )
+	pub fn record_stats(&mut self, recorder :StatsRecorder) {
+		self.recorder = Some( recorder );
+	}
+
+	pub fn stats(&self) -> PopulationStats {
+		let living = self.organisms.iter().filter(|org| org.alive)
+			.map(|org| (org.max_fitness, org.age, org.genome.active_genes(), org.brain.size()));
+		PopulationStats { 
+			step: self.clock, 
+			births: self.births, 
+			deaths: self.deaths, 
+			..PopulationStats::collect( living ) 
+		}
+	}
+
+	pub fn is_extinct(&self) -> bool {
+		!self.organisms.iter().any(|org| org.alive)
+	}
+
+	// How winners of the offspring lottery get picked. Roulette unless you say otherwise.
+	pub fn set_selection<S :SelectionStrategy + 'static>(&mut self, selection :S) {
+		self.selection = Box::new( selection );
 	}
 
 	// The main loop sequence. Processes in chunks equal to avg_life
@@ -48,7 +275,7 @@ impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
 	}
 
 	pub fn advance(&mut self, total_steps :usize) {
-		if Config::get().independent {
+		if self.config.independent {
 			let avg = self.avg_life().floor() as usize;
 			let mut steps = 0;
 			// let's break loops up into life-span size chunks
@@ -61,26 +288,90 @@ impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
 		}
 	}
 
+	// Keeps going until one of the criteria is met (checked before the first step too).
+	// An empty StopCriteria would go forever, so that returns right away (StopReason::NoCriteria).
+	// Independent worlds go in batches of avg_life, same as advance(), so the checks happen between batches.
+	// Others get checked every step, but still only log "Processed steps" once per avg_life.
+	pub fn run_until(&mut self, mut criteria :StopCriteria) -> RunSummary {
+		let avg = self.avg_life().floor() as usize;
+		let mut stats = self.stats();
+		let mut run = RunState::new( &stats );
+		let mut stop = run.check( &mut criteria, &stats, false );
+		let mut unlogged = 0;
+		while stop.is_none() {
+			let extinctions = self.extinctions;
+			let steps = if self.config.independent {
+				usize::min( avg, criteria.steps_left( run.steps ) )
+			} else { 1 };
+			self.take_steps( steps );
+			run.steps += steps;
+			unlogged += steps;
+			if unlogged >= avg {
+				self.log_steps( unlogged );
+				unlogged = 0;
+			}
+			stats = self.stats();
+			stop = run.check( &mut criteria, &stats, self.extinctions > extinctions );
+		}
+		if unlogged > 0 { self.log_steps( unlogged ); }
+		let reason = stop.unwrap();
+		if self.config.log(Verbosity::Low) {
+			tracing::info!(step = self.clock, steps = run.steps, best = run.best_fitness, ?reason, "Run stopped");
+		}
+		run.summary( reason, stats )
+	}
+
 	// All control comes through steps. 
 	fn steps(&mut self, steps :usize) {
-		if Config::get().independent {
-			// assert!( steps <= Config::get().avg_life() ); 
+		self.take_steps( steps );
+		self.log_steps( steps );
+	}
+
+	fn take_steps(&mut self, steps :usize) {
+		if self.config.independent {
+			// assert!( steps <= self.avg_life() ); 
 			// It's okay, user can use how they want. 
 			// We'll just cap reproduction at population size.
+			self.births = 0;
+			self.deaths = 0;
 			for id in 0 .. self.organisms.len() { // iter().enumerate() {
 				// if !genome.alive { continue }
 				self.i_steps( id, &steps );
 			}
-			self.reproduce(&steps);
+			self.batch_done( steps );
 		} else {
 			for _s in 0..steps { 
+				self.births = 0;
+				self.deaths = 0;
 				self.step(); 
+				self.clock += 1;
 				self.reproduce(&1);
+				self.record();
 			}
 		}
-		
-		if Config::log("on") {
-			println!("Processing {} steps. {}", &steps, self.fitness_stats() );
+	}
+
+	// after an independent batch
+	fn batch_done(&mut self, steps :usize) {
+		self.clock += steps;
+		self.reproduce(&steps);
+		self.record();
+	}
+
+	fn log_steps(&self, steps :usize) {
+		if self.config.log(Verbosity::Low) {
+			let (max, pop) = self.max_fitness();
+			let sum = self.sum_fitness();
+			tracing::info!(step = self.clock, steps, sum, avg = sum/pop, max, "Processed steps");
+		}
+	}
+
+	fn record(&mut self) {
+		if self.recorder.is_none() { return }
+		let stats = self.stats();
+		if let Err(e) = self.recorder.as_mut().unwrap().record( &stats ) {
+			tracing::warn!(error = %e, "Couldn't record stats, recorder removed");
+			self.recorder = None;
 		}
 	}
 
@@ -97,8 +388,12 @@ impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
 
 		for _s in 0..*steps {
 			if !org.alive { break; };
-			org.take_step( &mut self.environs );
+			org.take_step( &mut self.environs, &self.config );
 			// Note, one would think you could abstract all these steps in org possibly... 
+			if !org.alive { 
+				self.fame.consider( org ); 
+				self.deaths += 1;
+			}
 		}
 	}
 	
@@ -110,7 +405,11 @@ impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
 		// now compute the outputs and act (TODO: let's do this in order of most fit)
 		for org in self.organisms.iter_mut() {
 			if !org.alive { continue; }
-			org.take_step( &mut self.environs );
+			org.take_step( &mut self.environs, &self.config );
+			if !org.alive { 
+				self.fame.consider( org ); 
+				self.deaths += 1;
+			}
 		}
 		// self.expunge_dead(); // [see: docs/expunge.txt]
 	}
@@ -118,20 +417,19 @@ impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
 	// Reproduction stuff ===========================================
 
 	fn avg_life(&self) -> f32 {
-		Config::get().lifespan as f32
+		self.config.lifespan as f32
 	}
 
 	// The fraction of the population that was depleted during these steps (based on avg_life)
-	fn offspring_needed(&self, steps :&usize) -> usize {
-		let mut needed = (steps * Config::get().population ) as f32 / self.avg_life();
-		let mut rng = rand::thread_rng();
+	fn offspring_needed(&mut self, steps :&usize) -> usize {
+		let mut needed = (steps * self.config.population ) as f32 / self.avg_life();
 
 		// fraction determines probability of additional child.
 		let fraction = needed - needed.floor();
-		if fraction >= rng.gen_range(0.0..1.0) { 
+		if fraction >= self.rng.gen_range(0.0..1.0) { 
 			needed += 1.; // yay a bonus child!
 		}
-		usize::min( needed.floor() as usize, Config::get().population ) // cap it at population size
+		usize::min( needed.floor() as usize, self.config.population ) // cap it at population size
 	 }
 
 	fn max_fitness(&self) -> (f32, f32) { // don't confuse with org.max_fitness :/ 
@@ -157,50 +455,91 @@ impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
 	fn reproduce(&mut self, steps :&usize) {
 		let needed = self.offspring_needed( steps );
 
-		// first pick the winners of offspring lottery
-        let mut rng = rand::thread_rng();
-		for _i in 0..needed { // self.offspring_needed( steps ) {
-			
-			// Pick a number, 0 - sum(org.max_fitness)
-			let num = rng.gen_range(0.0.. self.sum_fitness());
-			
-			// Then just cycle through the org.max_fitness, 
-			// until we find the "winner"
-			
-			let mut tot = 0.;
-			for (id, org) in self.organisms.iter().enumerate() {
-				if !org.alive { continue; }
-				tot += f32::abs(org.max_fitness); // abs: sanity check, could shift all numbers by greatest negative number... 
-				if tot >= num { // We have a winner
-					self.fertile.push( id );
-					break;
-				}
+		// first pick the winners of offspring lottery (among the living)
+		if needed > 0 {
+			let (ids, fitness) :(Vec<usize>, Vec<f32>) = self.organisms.iter().enumerate()
+				.filter(|(_, org)| org.alive )
+				.map(|(id, org)| (id, org.max_fitness) )
+				.unzip();
+			// every baby needs Config.parents winners
+			let family = self.config.parents;
+			let winners = self.selection.select( &fitness, needed * family, &mut self.rng );
+			for parents in winners.chunks_exact( family ) {
+				self.fertile.push( parents.iter().map(|w| ids[*w]).collect() );
 			}
 		}
 
-		if Config::log("low") && needed>0 { println!( "Winners: {:?}", &self.fertile ); }
+		if self.config.log(Verbosity::High) && needed>0 { 
+			tracing::debug!(step = self.clock, winners = ?self.fertile, "Offspring lottery");
+		}
 		
 		// great, we have some babies to make!
-		while self.fertile.len() > 0 {
-			if let Some(id) = self.fertile.pop() {
-				self.organisms[id].offspring += 1;
-				let org = self.organisms[id].bud( &mut self.environs );
-				// let env = self.organisms[id].environs; // pass along environs
-				self.birth( org );
+		while let Some(family) = self.fertile.pop() {
+			for id in family.iter() {
+				self.organisms[*id].offspring += 1;
 			}
+			let mut org = if family.len() == 1 {
+				self.organisms[family[0]].bud( &mut self.environs, &self.config, &mut self.rng )
+			} else {
+				let parents :Vec<&Organism<T>> = family.iter().map(|id| &self.organisms[*id]).collect();
+				Organism::breed( &parents, &mut self.environs, &self.config, &mut self.rng )
+			};
+			// let env = self.organisms[id].environs; // pass along environs
+			org.born = self.clock;
+			self.birth( org );
 		} // consider better reproduction strats! [see: docs/repro.txt]
+
+		self.keep_alive();
+	}
+
+	// Population floor. Top up to Config.min_population, or all the way back to Config.population if it died out.
+	fn keep_alive(&mut self) {
+		let living = self.organisms.iter().filter(|org| org.alive).count();
+		let extinct = living == 0;
+		if !extinct { self.extinct = false; } // back from the dead (ie load_population), the next extinction is news again
+		let target = if extinct { usize::max( self.config.population, self.config.min_population ) } else { self.config.min_population };
+		if living >= target || (extinct && self.extinct) { return }
+
+		let mut reseeded = 0;
+		if self.config.reseed != Reseed::None {
+			let champions = if self.config.reseed == Reseed::HallOfFame { self.hall_of_fame().champions().to_vec() } else { Vec::new() };
+			for i in 0..(target - living) {
+				let mut org = match champions.get( i % usize::max( champions.len(), 1 ) ) {
+					Some(champion) => Organism::from_genome( champion.genome.clone(), &mut self.environs, Vec::new(), &self.config ),
+					None => Organism::new( &mut self.environs, &self.config, &mut self.rng ),
+				};
+				org.born = self.clock;
+				self.birth( org );
+				reseeded += 1;
+			}
+		}
+
+		if extinct {
+			self.extinct = reseeded == 0;
+			self.extinctions += 1;
+			if self.config.log(Verbosity::Low) {
+				tracing::warn!(step = self.clock, reseeded, "Population went extinct");
+			}
+			let event = Extinction { step: self.clock, reseeded };
+			if let Some(callback) = self.on_extinction.as_mut() {
+				callback( &event );
+			}
+		} else if self.config.log(Verbosity::High) {
+			tracing::debug!(step = self.clock, living, reseeded, "Population below the floor");
+		}
 	}
 
 	fn birth(&mut self, baby :Organism<T>) {
+		self.births += 1;
 		// Check for "dead" body
 		for (id, org) in self.organisms.iter().enumerate() {
-			if !org.alive & !self.fertile.contains(&id) {
+			if !org.alive & !self.fertile.iter().any(|family| family.contains(&id)) {
 				// remove it from the fertility pool (if needed). You snooze you lose. 
 				// self.fertile.remove( self.fertile.iter().position(|x| *x == id).unwrap() );
 
 				// reclaim the body
 				self.organisms[id] = baby;
-				return ();
+				return;
 			}
 		}
 		
@@ -210,3 +549,602 @@ impl <E:Environs<Creature = T>, T:Creature<Env=E, CCT=T>> World<E,T> {
 }
 
 
+
+#[cfg(feature = "parallel")]
+impl <E, T> World<E,T> 
+where 
+	E :Environs<Creature = T> + Sync,
+	T :IndependentCreature<Env=E, CCT=T> + Send,
+{
+	// Like advance(), but each batch steps the organisms across threads (rayon).
+	// Only for Config.independent worlds, anything else just goes to advance().
+	pub fn par_advance(&mut self, total_steps :usize) {
+		if !self.config.independent { return self.advance( total_steps ) }
+		let avg = self.avg_life().floor() as usize;
+		let mut steps = 0;
+		while steps < total_steps {
+			self.par_steps( usize::min( avg, total_steps-steps) );
+			steps += avg;
+		}
+	}
+
+	pub fn par_live(&mut self) {
+		self.par_advance( self.avg_life().floor() as usize );
+	}
+
+	fn par_steps(&mut self, steps :usize) {
+		use rayon::prelude::*;
+		self.births = 0;
+		self.deaths = 0;
+		// one stream per organism, handed out in order so thread scheduling can't change the run
+		let seeds :Vec<u64> = (0..self.organisms.len()).map(|_| self.rng.gen()).collect();
+		let (env, cfg) = (&self.environs, &self.config);
+		let died :Vec<bool> = self.organisms.par_iter_mut().zip( seeds ).map(|(org, seed)| {
+			let mut rng = WorldRng::seed_from_u64( seed );
+			let alive = org.alive;
+			for _s in 0..steps {
+				if !org.alive { break; };
+				org.take_step_alone( env, cfg, &mut rng );
+			}
+			alive && !org.alive
+		}).collect();
+
+		for (org, _) in self.organisms.iter().zip( died ).filter(|(_, died)| *died) {
+			self.fame.consider( org );
+			self.deaths += 1;
+		}
+		self.batch_done( steps );
+		self.log_steps( steps );
+	}
+}
+
+// Checkpoints need to save your environment and creatures too, so they have to be serde friendly. 
+// ie: #[derive(Serialize, Deserialize)] struct MyEnv { ... }
+impl <E, T> World<E,T> 
+where E :Environs<Creature = T> + Serialize + DeserializeOwned, T :Creature<Env=E, CCT=T> + Serialize + DeserializeOwned {
+	// Saves the whole world, rng included, so a resumed run carries on exactly as this one would have.
+	// .bin/.bincode for bincode, otherwise json. 
+	// (The selection strategy isn't saved, set it again after resuming if you changed it.)
+	pub fn checkpoint<P :AsRef<Path>>(&self, path :P) -> Result<(), PersistError> {
+		let checkpoint = Checkpoint {
+			version: CHECKPOINT_VERSION,
+			config: self.config.clone(),
+			seed: self.seed,
+			rng: &self.rng,
+			clock: self.clock,
+			extinct: self.extinct,
+			extinctions: self.extinctions,
+			births: self.births,
+			deaths: self.deaths,
+			fertile: self.fertile.clone(),
+			hall_of_fame: self.fame.records(),
+			organisms: self.organisms.iter().map(|org| OrganismState {
+				genome: org.genome.to_hex(),
+				node_state: org.brain.node_state.clone(),
+				alive: org.alive,
+				age: org.age,
+				offspring: org.offspring,
+				born: org.born,
+				fitness: org.fitness,
+				max_fitness: org.max_fitness,
+				creature: &org.creature,
+			}).collect(),
+			environs: &self.environs,
+		};
+		persist::write_file( path.as_ref(), &checkpoint )
+	}
+
+	pub fn resume<P :AsRef<Path>>(path :P) -> Result<Self, PersistError> {
+		let checkpoint :Checkpoint<E, T, WorldRng> = persist::read_file( path.as_ref() )?;
+		if checkpoint.version != CHECKPOINT_VERSION {
+			return Err( PersistError::Version( checkpoint.version.to_string() ) )
+		}
+		let config = checkpoint.config;
+		config.validate().map_err(|e| PersistError::Mismatch( e.to_string() ))?;
+		// a TypedCreature maps nodes by position, so a reordered Sensors/Actions enum must not line up with old genomes
+		check_names( "inputs", &config.inputs, T::INPUTS ).map_err(|e| PersistError::Mismatch( e.to_string() ))?;
+		check_names( "outputs", &config.outputs, T::OUTPUTS ).map_err(|e| PersistError::Mismatch( e.to_string() ))?;
+
+		let mut organisms = Vec::new();
+		for state in checkpoint.organisms {
+			let mut org = Organism::restore( Genome::from_hex( &state.genome, &config )?, state.creature, &config );
+			if state.node_state.len() != org.brain.node_state.len() {
+				return Err( PersistError::Mismatch( "brain size".to_string() ) )
+			}
+			org.brain.node_state = state.node_state;
+			org.alive = state.alive;
+			org.age = state.age;
+			org.offspring = state.offspring;
+			org.born = state.born;
+			org.fitness = state.fitness;
+			org.max_fitness = state.max_fitness;
+			organisms.push( org );
+		}
+
+		let fame = HallOfFame::from_records( checkpoint.hall_of_fame, &config )?;
+		Ok( Self {
+			organisms,
+			environs: checkpoint.environs,
+			config,
+			seed: checkpoint.seed,
+			rng: checkpoint.rng,
+			clock: checkpoint.clock,
+			selection: Box::new( Roulette ),
+			fame,
+			extinct: checkpoint.extinct,
+			extinctions: checkpoint.extinctions,
+			on_extinction: None,
+			births: checkpoint.births,
+			deaths: checkpoint.deaths,
+			recorder: None,
+			fertile: checkpoint.fertile,
+		})
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::math;
+
+	// A tiny world for the tests: creatures wander along X and get rewarded for distance from home
+	#[derive(Serialize, serde::Deserialize)]
+	pub(crate) struct Line {}
+
+	impl Environs for Line {
+		type Creature = Walker;
+		fn new() -> Self { Self{} }
+	}
+
+	#[derive(Serialize, serde::Deserialize)]
+	pub(crate) struct Walker {
+		pub(crate) x :f32,
+		pub(crate) parents :usize,
+	}
+
+	impl Creature for Walker {
+		type Env = Line;
+		type CCT = Self;
+		fn new( _env :&mut Line, parents :Vec<&Self> ) -> Self { Self { x: 0., parents: parents.len() } }
+		fn rx_input( &self, _input :&str, _env :&Line ) -> f32 { self.x }
+		fn tx_output( &mut self, _output :&str, value :f32, _env :&Line ) { self.x += math::tanh( value ); }
+		fn act( &mut self, _env :&mut Line ) -> f32 { f32::abs( self.x ) }
+	}
+
+	impl IndependentCreature for Walker {
+		fn act_alone( &mut self, _env :&Line, rng :&mut WorldRng ) -> f32 { f32::abs( self.x ) + rng.gen::<f32>() }
+	}
+
+	// Walker again, but all at once
+	struct BatchLine {}
+	impl Environs for BatchLine {
+		type Creature = BatchWalker;
+		fn new() -> Self { Self {} }
+	}
+
+	struct BatchWalker { x :f32, actuated :usize }
+	impl Creature for BatchWalker {
+		type Env = BatchLine;
+		type CCT = Self;
+		fn new( _env :&mut BatchLine, _parents :Vec<&Self> ) -> Self { Self { x: 0., actuated: 0 } }
+		fn rx_input( &self, _input :&str, _env :&BatchLine ) -> f32 { panic!("sense covers it") }
+		fn tx_output( &mut self, _output :&str, _value :f32, _env :&BatchLine ) { panic!("actuate covers it") }
+		fn sense( &self, _env :&BatchLine, out :&mut [f32], mask :&NodeMask ) {
+			assert_eq!( (out.len(), mask.len(), mask.name(1)), (2, 2, "ONE") );
+			for i in mask.active() { out[i] = self.x; }
+		}
+		fn actuate( &mut self, _env :&BatchLine, outputs :&[f32], mask :&NodeMask ) {
+			self.actuated += 1;
+			for i in mask.active() { self.x += math::tanh( outputs[i] ); }
+		}
+		fn act( &mut self, _env :&mut BatchLine ) -> f32 { f32::abs( self.x ) }
+	}
+
+	pub(crate) fn config() -> Config {
+		Config::builder()
+			.inputs(["X", "ONE"])
+			.outputs(["MOVX"])
+			.neurons(3)
+			.population(20)
+			.lifespan(10)
+			.genome_size(3)
+			.verbose(Verbosity::Silent)
+			.seed(7)
+			.build()
+			.unwrap()
+	}
+
+	fn dna(world :&World<Line, Walker>) -> Vec<u32> {
+		world.organisms.iter().flat_map(|org| org.genome.chromos.iter().flat_map(|c| c.genes.iter().map(|g| g.dna))).collect()
+	}
+
+	fn fitness(world :&World<Line, Walker>) -> Vec<u32> {
+		world.organisms.iter().map(|org| org.fitness.to_bits()).collect()
+	}
+
+	#[test]
+	fn same_seed_same_run() {
+		let mut a :World<Line, Walker> = World::new( config() );
+		let mut b :World<Line, Walker> = World::new( config() );
+		assert_eq!( a.seed(), 7 );
+		assert_eq!( dna(&a), dna(&b) );
+		for _ in 0..30 {
+			a.advance(1);
+			b.advance(1);
+			assert_eq!( fitness(&a), fitness(&b) );
+		}
+		assert_eq!( dna(&a), dna(&b) );
+
+		let c :World<Line, Walker> = World::new( Config { seed: Some(8), ..config() } );
+		assert_ne!( dna(&a), dna(&c) );
+	}
+
+	// Keeps (message, field names) of every tracing event
+	type Event = (String, Vec<String>);
+	struct Capture( std::sync::Arc<std::sync::Mutex<Vec<Event>>> );
+
+	impl<S :tracing::Subscriber> tracing_subscriber::Layer<S> for Capture {
+		fn on_event(&self, event :&tracing::Event<'_>, _ctx :tracing_subscriber::layer::Context<'_, S>) {
+			struct Fields( String, Vec<String> );
+			impl tracing::field::Visit for Fields {
+				fn record_debug(&mut self, field :&tracing::field::Field, value :&dyn std::fmt::Debug) {
+					if field.name() == "message" { self.0 = format!("{:?}", value); } else { self.1.push( field.name().to_string() ); }
+				}
+			}
+			let mut fields = Fields( String::new(), Vec::new() );
+			event.record( &mut fields );
+			self.0.lock().unwrap().push( (fields.0, fields.1) );
+		}
+	}
+
+	fn events(verbose :Verbosity) -> Vec<Event> {
+		capture( Config { verbose, ..config() }, |world| world.advance( 20 ) )
+	}
+
+	fn capture<F :FnOnce(&mut World<Line, Walker>)>(config :Config, run :F) -> Vec<Event> {
+		use tracing_subscriber::layer::SubscriberExt;
+		let events = std::sync::Arc::new( std::sync::Mutex::new( Vec::new() ) );
+		let subscriber = tracing_subscriber::registry().with( Capture( events.clone() ) );
+		tracing::subscriber::with_default( subscriber, || {
+			let mut world :World<Line, Walker> = World::new( config );
+			run( &mut world );
+		});
+		let events = events.lock().unwrap().clone();
+		events
+	}
+
+	#[test]
+	fn logs_by_verbosity() {
+		let named = |events :&[Event], message :&str| events.iter().filter(|(m, _)| m == message).cloned().collect::<Vec<_>>();
+
+		assert!( events( Verbosity::Silent ).is_empty() );
+
+		let low = events( Verbosity::Low );
+		let summaries = named( &low, "Processed steps" );
+		assert!( !summaries.is_empty() );
+		for field in ["step", "sum", "avg", "max"] {
+			assert!( summaries.iter().all(|(_, fields)| fields.iter().any(|f| f == field)), "missing {}", field );
+		}
+		assert!( named( &low, "Offspring lottery" ).is_empty() ); // winners are High only (they were "low" before tracing)
+
+		let high = events( Verbosity::High );
+		assert!( !named( &high, "Offspring lottery" ).is_empty() );
+		assert_eq!( named( &high, "Processed steps" ).len(), summaries.len() );
+
+		// run_until steps one at a time, but doesn't say so every time
+		let cfg = Config { verbose: Verbosity::Low, lifespan: 10, ..config() };
+		let run = capture( cfg, |world| { world.run_until( StopCriteria::new().max_steps( 25 ) ); } );
+		assert_eq!( named( &run, "Processed steps" ).len(), 3 ); // 10, 10, and the last 5
+		assert_eq!( named( &run, "Run stopped" ).len(), 1 );
+	}
+
+	#[test]
+	fn dot_uses_the_world_config() {
+		let world :World<Line, Walker> = World::new( Config { inputs: vec!["X".into(), "Y".into(), "ONE".into()], seed: Some(3), ..config() } );
+		let dot = world.to_dot( 0, true );
+		assert!( dot.starts_with( "digraph" ) );
+		for name in world.config().inputs.iter().chain( world.config().outputs.iter() ) {
+			assert!( dot.contains( name.as_str() ), "missing {}", name );
+		}
+	}
+
+	// no more global config: worlds with different shapes don't step on each other, on this thread or another
+	#[test]
+	fn worlds_side_by_side() {
+		let wide = || Config { inputs: vec!["X".into(), "Y".into(), "ONE".into()], neurons: 6, seed: Some(9), ..config() };
+		let mut narrow :World<Line, Walker> = World::new( config() );
+		let mut wide_here :World<Line, Walker> = World::new( wide() );
+		let wide_there = std::thread::spawn( move || {
+			let mut world :World<Line, Walker> = World::new( wide() );
+			world.advance( 30 );
+			(dna(&world), fitness(&world))
+		});
+		for _ in 0..30 {
+			narrow.advance(1);
+			wide_here.advance(1);
+		}
+
+		for (world, nodes) in [(&narrow, 2 + 3 + 1), (&wide_here, 3 + 6 + 1)] {
+			assert_eq!( world.config().node_count(), nodes );
+			for org in world.organisms.iter() {
+				assert_eq!( org.brain.node_state.len(), nodes );
+				for gene in org.genome.chromos.iter().flat_map(|c| c.genes.iter()) {
+					assert!( gene.source.idx < nodes && gene.sink.idx < nodes );
+				}
+			}
+		}
+		assert_eq!( wide_there.join().unwrap(), (dna(&wide_here), fitness(&wide_here)) );
+	}
+
+	#[test]
+	fn senses_in_batches() {
+		let mut a :World<Line, Walker> = World::new( config() );
+		let mut b :World<BatchLine, BatchWalker> = World::new( config() );
+		for _ in 0..30 {
+			a.advance(1);
+			b.advance(1);
+			let fitness_b :Vec<u32> = b.organisms.iter().map(|org| org.fitness.to_bits()).collect();
+			assert_eq!( fitness(&a), fitness_b );
+		}
+		assert!( b.organisms.iter().all(|org| org.creature.actuated == org.age) );
+	}
+
+	#[test]
+	fn feedforward_world() {
+		let mut lagged :World<Line, Walker> = World::new( config() );
+		let mut ff :World<Line, Walker> = World::new( Config { evaluation: crate::Evaluation::Feedforward, ..config() } );
+		assert_eq!( dna(&lagged), dna(&ff) );
+		for org in lagged.organisms.iter_mut().chain( ff.organisms.iter_mut() ) { org.creature.x = 1.; } // something to sense
+		lagged.advance(1);
+		ff.advance(1);
+		assert_ne!( fitness(&lagged), fitness(&ff) ); // hidden layers got through on the first step
+		ff.advance(50);
+	}
+
+	#[test]
+	fn thinks_between_steps() {
+		let mut a :World<Line, Walker> = World::new( Config { think_ticks: 3, ..config() } );
+		let mut b :World<Line, Walker> = World::new( config() );
+		for (x, y) in a.organisms.iter_mut().zip( b.organisms.iter_mut() ) {
+			x.creature.x = 1.;
+			y.creature.x = 1.;
+			x.take_step( &mut a.environs, &a.config );
+			y.get_inputs( &b.environs, &b.config );
+			for _ in 0..3 { y.brain.process(); }
+			assert_eq!( x.brain.output_values, y.brain.output_values );
+		}
+	}
+
+	#[test]
+	fn breeds_with_all_parents() {
+		let mut world :World<Line, Walker> = World::new( Config { parents: 3, crossover: crate::Crossover::NPoint(2), ..config() } );
+		world.advance( 25 );
+		let babies :Vec<&Walker> = world.organisms.iter().map(|org| &org.creature).filter(|w| w.parents > 0).collect();
+		assert!( !babies.is_empty() );
+		assert!( babies.iter().all(|w| w.parents == 3) );
+		assert!( world.organisms.iter().all(|org| org.genome.chromos.len() == world.config().genome_size) );
+	}
+
+	#[test]
+	fn save_and_load_population() {
+		let dir = std::env::temp_dir().join( format!("gworld-population-{}", std::process::id()) );
+		std::fs::create_dir_all( &dir ).unwrap();
+		let mut world :World<Line, Walker> = World::new( config() );
+		world.advance( 15 );
+		let living :Vec<String> = world.organisms.iter().filter(|org| org.alive).map(|org| org.genome().to_hex()).collect();
+
+		for file in ["pop.json", "pop.bin"] {
+			let path = dir.join( file );
+			world.save_population( &path ).unwrap();
+			let mut fresh :World<Line, Walker> = World::new( Config { seed: Some(99), ..config() } );
+			fresh.load_population( &path ).unwrap();
+			assert_eq!( fresh.organisms.iter().map(|org| org.genome().to_hex()).collect::<Vec<String>>(), living );
+			fresh.advance( 5 );
+
+			let mut other :World<Line, Walker> = World::new( Config { neurons: 4, ..config() } );
+			assert!( matches!( other.load_population( &path ), Err( PersistError::Mismatch(_) ) ) );
+		}
+		std::fs::remove_dir_all( &dir ).ok();
+	}
+
+	#[test]
+	fn checkpoint_and_resume() {
+		let dir = std::env::temp_dir().join( format!("gworld-checkpoint-{}", std::process::id()) );
+		std::fs::create_dir_all( &dir ).unwrap();
+		for file in ["world.json", "world.bin"] {
+			let path = dir.join( file );
+			let mut world :World<Line, Walker> = World::new( Config { parents: 2, ..config() } );
+			world.advance( 12 );
+			world.checkpoint( &path ).unwrap();
+			let mut resumed :World<Line, Walker> = World::resume( &path ).unwrap();
+			assert_eq!( (resumed.clock(), resumed.seed()), (12, 7) );
+			assert_eq!( resumed.stats(), world.stats() ); // births and deaths too
+			for _ in 0..20 {
+				world.advance(1);
+				resumed.advance(1);
+				assert_eq!( fitness(&world), fitness(&resumed) );
+			}
+			assert_eq!( dna(&world), dna(&resumed) );
+			let xs = |w :&World<Line, Walker>| w.organisms.iter().map(|org| org.creature.x.to_bits()).collect::<Vec<u32>>();
+			assert_eq!( xs(&world), xs(&resumed) );
+		}
+		std::fs::remove_dir_all( &dir ).ok();
+	}
+
+	#[test]
+	fn hall_of_fame_keeps_the_best() {
+		let mut world :World<Line, Walker> = World::new( Config { hall_of_fame: 4, ..config() } );
+		world.advance( 40 );
+		let fame = world.hall_of_fame();
+		assert_eq!( fame.len(), 4 );
+		let best = fame.champions().iter().map(|c| c.max_fitness).collect::<Vec<f32>>();
+		assert!( best.windows(2).all(|w| w[0] >= w[1]) );
+		// nobody, dead or alive, beats the best
+		assert!( world.organisms.iter().all(|org| org.max_fitness <= best[0]) );
+		assert!( world.fame.champions().iter().all(|c| c.age > world.config().lifespan) ); // archived at death
+		assert!( fame.champions().iter().all(|c| c.born <= world.clock()) );
+
+		let path = std::env::temp_dir().join( format!("gworld-fame-{}.json", std::process::id()) );
+		fame.save( &path ).unwrap();
+		let loaded = HallOfFame::load( &path ).unwrap();
+		std::fs::remove_file( &path ).ok();
+		assert_eq!( loaded.config().inputs, world.config().inputs );
+		for (a, b) in loaded.champions().iter().zip( fame.champions() ) {
+			assert_eq!( (a.genome.to_hex(), a.max_fitness, a.age, a.offspring, a.born), (b.genome.to_hex(), b.max_fitness, b.age, b.offspring, b.born) );
+		}
+		assert_eq!( loaded.len(), 4 );
+	}
+
+	// everybody dies of old age on the next step, and can't have kids
+	fn doomed() -> Config {
+		Config { lifespan: 1_000_000, population: 5, ..config() }
+	}
+
+	fn kill_all(world :&mut World<Line, Walker>) {
+		for org in world.organisms.iter_mut() { org.age = world.config.lifespan; }
+	}
+
+	#[test]
+	fn extinction_gets_rescued() {
+		use std::{rc::Rc, cell::RefCell};
+		let events = Rc::new( RefCell::new( Vec::new() ) );
+
+		for reseed in [Reseed::Random, Reseed::HallOfFame, Reseed::None] {
+			let mut world :World<Line, Walker> = World::new( Config { reseed, ..doomed() } );
+			let seen = events.clone();
+			world.on_extinction( move |e| seen.borrow_mut().push( (reseed, e.reseeded) ) );
+			kill_all( &mut world );
+			world.advance(1);
+			world.advance(1);
+			assert_eq!( world.is_extinct(), reseed == Reseed::None );
+		}
+		// reported once each, even though the unrescued world stayed dead for two steps
+		assert_eq!( *events.borrow(), vec![(Reseed::Random, 5), (Reseed::HallOfFame, 5), (Reseed::None, 0)] );
+	}
+
+	#[test]
+	fn extinct_reload_extinct() {
+		let path = std::env::temp_dir().join( format!("gworld-reload-{}.json", std::process::id()) );
+		let mut world :World<Line, Walker> = World::new( Config { reseed: Reseed::None, ..doomed() } );
+		world.save_population( &path ).unwrap();
+		let fired = std::rc::Rc::new( std::cell::Cell::new( 0 ) );
+		let seen = fired.clone();
+		world.on_extinction( move |_| seen.set( seen.get() + 1 ) );
+
+		for _ in 0..2 {
+			kill_all( &mut world );
+			world.advance(1);
+			assert!( world.is_extinct() );
+			world.load_population( &path ).unwrap();
+		}
+		std::fs::remove_file( &path ).ok();
+		assert_eq!( (fired.get(), world.extinctions), (2, 2) );
+	}
+
+	// an extinction that was already reported stays reported after a resume
+	#[test]
+	fn resumes_extinct() {
+		let path = std::env::temp_dir().join( format!("gworld-extinct-{}.json", std::process::id()) );
+		let mut world :World<Line, Walker> = World::new( Config { reseed: Reseed::None, ..doomed() } );
+		kill_all( &mut world );
+		world.advance(1);
+		world.checkpoint( &path ).unwrap();
+
+		let mut resumed :World<Line, Walker> = World::resume( &path ).unwrap();
+		std::fs::remove_file( &path ).ok();
+		assert_eq!( (resumed.extinct, resumed.extinctions), (true, 1) );
+		let fired = std::rc::Rc::new( std::cell::Cell::new( false ) );
+		let seen = fired.clone();
+		resumed.on_extinction( move |_| seen.set( true ) );
+		resumed.advance(1);
+		assert_eq!( (fired.get(), resumed.extinctions), (false, 1) );
+	}
+
+	#[test]
+	fn population_floor() {
+		let mut world :World<Line, Walker> = World::new( Config { min_population: 3, ..doomed() } );
+		for org in world.organisms.iter_mut().skip(1) { org.age = world.config.lifespan; }
+		world.advance(1);
+		assert_eq!( world.organisms.iter().filter(|org| org.alive).count(), 3 );
+	}
+
+	#[cfg(feature = "parallel")]
+	#[test]
+	fn parallel_runs_match() {
+		let run = |threads :usize| {
+			let pool = rayon::ThreadPoolBuilder::new().num_threads( threads ).build().unwrap();
+			pool.install(|| {
+				let mut world :World<Line, Walker> = World::new( Config { independent: true, ..config() } );
+				world.par_advance( 45 );
+				assert_eq!( world.clock(), 45 );
+				assert!( world.stats().births > 0 );
+				(dna(&world), fitness(&world))
+			})
+		};
+		assert_eq!( run(1), run(4) );
+	}
+
+	#[test]
+	fn runs_until() {
+		use crate::{StopCriteria, StopReason};
+		let mut world :World<Line, Walker> = World::new( config() );
+		let summary = world.run_until( StopCriteria::new().max_steps(25) );
+		assert_eq!( (summary.reason, summary.steps, world.clock()), (StopReason::MaxSteps, 25, 25) );
+		assert_eq!( summary.stats.step, 25 );
+
+		let summary = world.run_until( StopCriteria::new() );
+		assert_eq!( (summary.reason, summary.steps, world.clock()), (StopReason::NoCriteria, 0, 25) );
+
+		let target = summary.best_fitness;
+		let summary = world.run_until( StopCriteria::new().target_fitness( target ).max_steps(1000) );
+		assert_eq!( (summary.reason, summary.steps), (StopReason::TargetFitness, 0) ); // already there
+
+		let summary = world.run_until( StopCriteria::new().until(|stats| stats.step >= 40).max_steps(1000) );
+		assert_eq!( (summary.reason, world.clock()), (StopReason::User, 40) );
+
+		let summary = world.run_until( StopCriteria::new().stagnation(1).max_steps(1000) );
+		assert_eq!( summary.reason, StopReason::Stagnation );
+		assert!( summary.steps >= 1 );
+
+		let mut world :World<Line, Walker> = World::new( Config { independent: true, ..config() } );
+		let summary = world.run_until( StopCriteria::new().max_steps(25) );
+		assert_eq!( (summary.reason, summary.steps, world.clock()), (StopReason::MaxSteps, 25, 25) ); // batches of 10, 10, 5
+
+		for reseed in [Reseed::None, Reseed::Random] {
+			let mut world :World<Line, Walker> = World::new( Config { reseed, ..doomed() } );
+			kill_all( &mut world );
+			let summary = world.run_until( StopCriteria::new().extinction().max_steps(1000) );
+			assert_eq!( (summary.reason, summary.steps), (StopReason::Extinction, 1) );
+		}
+	}
+
+	#[test]
+	fn records_stats() {
+		let path = std::env::temp_dir().join( format!("gworld-stats-{}.csv", std::process::id()) );
+		std::fs::remove_file( &path ).ok();
+		let mut world :World<Line, Walker> = World::new( config() );
+		world.record_stats( crate::StatsRecorder::csv( &path ).unwrap() );
+		world.advance( 12 );
+		world.recorder = None; // flushed
+		world.record_stats( crate::StatsRecorder::csv( &path ).unwrap() ); // reopened: no second header
+		world.advance( 3 );
+		world.recorder = None;
+
+		let csv = std::fs::read_to_string( &path ).unwrap();
+		let rows :Vec<&str> = csv.lines().collect();
+		assert_eq!( rows.len(), 16 );
+		assert!( rows[0].starts_with("step,living,births,deaths") );
+		assert!( rows[1].starts_with("1,") );
+		assert!( rows[15].starts_with("15,") );
+
+		let stats = world.stats();
+		assert_eq!( stats.step, 15 );
+		assert_eq!( stats.living, world.organisms.iter().filter(|org| org.alive).count() );
+		assert!( stats.min <= stats.median && stats.median <= stats.max );
+		assert!( stats.mean_brain_size > 0. );
+		// lifespan is 10, so there has been turnover
+		let births :usize = rows[1..].iter().map(|row| row.split(',').nth(2).unwrap().parse::<usize>().unwrap()).sum();
+		assert!( births > 0 );
+		std::fs::remove_file( &path ).ok();
+	}
+}
@@ -0,0 +1,54 @@
+// Generated by gworld from an evolved genome, don't edit (regenerate it instead).
+#[allow(unused, clippy::all)]
+pub mod feedforward {
+	pub const INPUTS :usize = 2;
+	pub const OUTPUTS :usize = 1;
+	pub const INPUT_NAMES :[&str; INPUTS] = ["X", "ONE"];
+	pub const OUTPUT_NAMES :[&str; OUTPUTS] = ["MOVX"];
+	const NODES :usize = 6;
+	const THINK_TICKS :usize = 2;
+
+	pub struct Brain {
+		state :[f32; NODES], // inputs, then hidden (outputs are never read back)
+		outputs :[f32; OUTPUTS],
+	}
+
+	impl Brain {
+		pub const fn new() -> Self {
+			Self { state: [0.; NODES], outputs: [0.; OUTPUTS] }
+		}
+
+		// back to a newborn's blank state
+		pub fn reset(&mut self) {
+			*self = Self::new();
+		}
+
+		// One step. inputs in INPUT_NAMES order, outputs come back in OUTPUT_NAMES order.
+		pub fn step(&mut self, inputs :&[f32; INPUTS]) -> &[f32; OUTPUTS] {
+			self.state[ ..INPUTS ].copy_from_slice( inputs );
+			for _tick in 0..THINK_TICKS {
+				self.tick();
+			}
+			&self.outputs
+		}
+
+		fn tick(&mut self) {
+			let s = &mut self.state;
+			let prev = *s;
+			let r0 = 0f32 + s[0] * 2.000061_f32 + prev[3] * 1.0000305_f32;
+			s[2] = sigmoid(r0);
+			let r1 = 0f32 + s[2] * 2.000061_f32;
+			s[3] = sigmoid(r1);
+			let r2 = 0f32 + s[3] * 2.000061_f32;
+			self.outputs[0] = leaky_relu(r2);
+		}
+	}
+
+	fn sigmoid(x :f32) -> f32 {
+		1.0 / (1.0 + f32::exp(-x))
+	}
+
+	fn leaky_relu(x :f32) -> f32 {
+		if x > 0.0 { x } else { 0.01 * x }
+	}
+}
@@ -0,0 +1,55 @@
+// Generated by gworld from an evolved genome, don't edit (regenerate it instead).
+#[allow(unused, clippy::all)]
+pub mod lagged {
+	pub const INPUTS :usize = 3;
+	pub const OUTPUTS :usize = 3;
+	pub const INPUT_NAMES :[&str; INPUTS] = ["X", "Y", "ONE"];
+	pub const OUTPUT_NAMES :[&str; OUTPUTS] = ["MOVX", "MOVY", "EAT"];
+	const NODES :usize = 10;
+	const THINK_TICKS :usize = 1;
+
+	pub struct Brain {
+		state :[f32; NODES], // inputs, then hidden (outputs are never read back)
+		outputs :[f32; OUTPUTS],
+	}
+
+	impl Brain {
+		pub const fn new() -> Self {
+			Self { state: [0.; NODES], outputs: [0.; OUTPUTS] }
+		}
+
+		// back to a newborn's blank state
+		pub fn reset(&mut self) {
+			*self = Self::new();
+		}
+
+		// One step. inputs in INPUT_NAMES order, outputs come back in OUTPUT_NAMES order.
+		pub fn step(&mut self, inputs :&[f32; INPUTS]) -> &[f32; OUTPUTS] {
+			self.state[ ..INPUTS ].copy_from_slice( inputs );
+			for _tick in 0..THINK_TICKS {
+				self.tick();
+			}
+			&self.outputs
+		}
+
+		fn tick(&mut self) {
+			let s = &mut self.state;
+			let r0 = 0f32 + s[1] * 3.1353495_f32;
+			let r1 = 0f32 + s[0] * -3.3440962_f32;
+			let r2 = 0f32 + s[6] * -1.6343272_f32 + s[1] * -2.9937437_f32;
+			let r3 = 0f32 + s[0] * 2.2749717_f32 + s[1] * 1.6555681_f32;
+			let r4 = 0f32 + s[2] * -5.2765284_f32 + s[3] * 2.1289713_f32 + s[1] * 3.8800013_f32;
+			s[6] = tanh(r0);
+			s[3] = tanh(r1);
+			self.outputs[2] = r2;
+			self.outputs[0] = r3;
+			self.outputs[1] = r4;
+		}
+	}
+
+	fn tanh(x :f32) -> f32 {
+		let e1 = f32::exp(x);
+		let e2 = f32::exp(-x);
+		(e1 - e2) / (e1 + e2)
+	}
+}
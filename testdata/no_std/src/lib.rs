@@ -0,0 +1,5 @@
+// Network::to_rust_no_std output, built with only core and libm in reach. 
+// Part of the workspace, so `cargo build --workspace` fails if the generated code needs std.
+#![no_std]
+
+include!("../../embedded_brain.rs");
@@ -0,0 +1,55 @@
+// Its own test binary, so the counting allocator doesn't sit under every other test.
+use gworld::{World, Config, Verbosity, Environs, Creature, math};
+
+// counts this thread's allocations, so the test harness's own threads don't get in the way
+struct Counting;
+thread_local! { static ALLOCS :std::cell::Cell<usize> = const { std::cell::Cell::new(0) }; }
+unsafe impl std::alloc::GlobalAlloc for Counting {
+	unsafe fn alloc(&self, layout :std::alloc::Layout) -> *mut u8 {
+		ALLOCS.with(|n| n.set( n.get() + 1 ));
+		std::alloc::System.alloc( layout )
+	}
+	unsafe fn dealloc(&self, ptr :*mut u8, layout :std::alloc::Layout) {
+		std::alloc::System.dealloc( ptr, layout )
+	}
+}
+#[global_allocator]
+static ALLOCATOR :Counting = Counting;
+
+struct Line {}
+impl Environs for Line {
+	type Creature = Walker;
+	fn new() -> Self { Self {} }
+}
+
+struct Walker { x :f32 }
+impl Creature for Walker {
+	type Env = Line;
+	type CCT = Self;
+	fn new( _env :&mut Line, _parents :Vec<&Self> ) -> Self { Self { x: 1. } }
+	fn rx_input( &self, _input :&str, _env :&Line ) -> f32 { self.x }
+	fn tx_output( &mut self, _output :&str, value :f32, _env :&Line ) { self.x += math::tanh( value ); }
+	fn act( &mut self, _env :&mut Line ) -> f32 { f32::abs( self.x ) }
+}
+
+// World's step loop: sensing, thinking, acting, aging, and the reproduction bookkeeping when nobody's due.
+// Births themselves are out of scope, a baby needs a new genome and brain.
+#[test]
+fn steps_dont_allocate() {
+	let config = Config::builder()
+		.inputs(["X", "ONE"])
+		.outputs(["MOVX"])
+		.neurons(3)
+		.population(20)
+		.lifespan(1_000_000_000) // so no offspring are needed
+		.verbose(Verbosity::Silent)
+		.seed(7)
+		.build()
+		.unwrap();
+	let mut world :World<Line, Walker> = World::new( config );
+	world.advance(1);
+	let before = ALLOCS.with(|n| n.get());
+	world.advance(5);
+	assert_eq!( ALLOCS.with(|n| n.get()), before );
+	assert_eq!( world.clock(), 6 );
+}